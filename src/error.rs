@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 /// 错误权举
 #[derive(Debug)]
@@ -6,6 +8,89 @@ pub enum Error {
     Null,
     /// 表示序列化或反序列化JSON数据时可能发生的所有错误。
     Error(serde_json::error::Error),
+    /// 网络错误 (连接失败, 超时, 读取响应失败等)
+    Http(reqwest::Error),
+    /// 服务器返回了非 2xx 的状态码 (401 / 429 除外), 例: 403 权限不足 (缺少 scope)
+    Status {
+        /// HTTP 状态码
+        status: u16,
+        /// 响应内容, 有 `{"error": "..."}` 时为其中的信息
+        body: String,
+    },
+    /// osu! 在 2xx 的响应中返回的 `{"error": "..."}`
+    Api(String),
+    /// 认证失败 (授权失败, 无法获取 / 刷新 token)
+    Auth(String),
+    /// 401: API KEY 错误, 或者 token 无效 / 已过期
+    Unauthorized(String),
+    /// 请求过于频繁 (429)
+    RateLimited {
+        /// 响应头 `Retry-After` 给出的等待时间
+        retry_after: Option<Duration>,
+    },
+    /// 参数不合法, 请求没有被发送
+    InvalidInput(String),
+    /// 读写文件失败 (例: 保存 token)
+    Io(std::io::Error),
 }
 
-pub type Result<T> = std::result::Result<T,Error>;
\ No newline at end of file
+impl Error {
+    /// 服务器返回的 HTTP 状态码, 不是因为状态码失败的为 None
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Status { status, .. } => Some(*status),
+            Error::Unauthorized(_) => Some(401),
+            Error::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Null => write!(f, "获取的数据为空"),
+            Error::Error(err) => write!(f, "JSON 解析错误: {}", err),
+            Error::Http(err) => write!(f, "网络错误: {}", err),
+            Error::Status { status, body } => write!(f, "HTTP 状态码 {}: {}", status, body),
+            Error::Api(msg) => write!(f, "osu! api 错误: {}", msg),
+            Error::Auth(msg) => write!(f, "认证失败: {}", msg),
+            Error::Unauthorized(msg) => write!(f, "未授权: {}", msg),
+            Error::RateLimited { retry_after: Some(after) } => write!(f, "请求过于频繁, {:?} 后重试", after),
+            Error::RateLimited { retry_after: None } => write!(f, "请求过于频繁"),
+            Error::InvalidInput(msg) => write!(f, "参数错误: {}", msg),
+            Error::Io(err) => write!(f, "读写文件失败: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Error(err) => Some(err),
+            Error::Http(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::error::Error> for Error {
+    fn from(err: serde_json::error::Error) -> Self {
+        Error::Error(err)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T,Error>;
@@ -0,0 +1,238 @@
+//! 响应缓存
+//!
+//! 只缓存成功并且能够解析的 GET 请求 (v1 状态码 200 的 `{"error": ...}` 不会缓存),
+//! 缓存的 key 为 `协议 + host + 端口 + 路径 + 排序后的查询参数 (不包括 api key) + token 的哈希`。
+//!
+//! v2 的 key 中有 access token 的哈希, 多个用户的 `ApiV2` 共用一个 [`Cache`] 时不会拿到别人的数据
+//! (例: `type=friend` 的排行榜), token 刷新后之前的缓存不再命中。
+
+use std::collections::{BTreeMap, HashMap};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use reqwest::Url;
+
+/// # CacheBackend
+/// 缓存的存储方式, 默认提供内存中的 [`MemoryCache`], 需要存到硬盘/redis 的话自己实现这个 trait。
+pub trait CacheBackend: Debug + Send + Sync {
+    /// 取出没有过期的缓存
+    fn get(&self, key: &str) -> Option<String>;
+    /// 存入缓存, `ttl` 后过期
+    fn set(&self, key: &str, value: String, ttl: Duration);
+    /// 删除缓存
+    fn remove(&self, key: &str);
+}
+
+/// # MemoryCache
+/// 内存中的 LRU 缓存, 超过容量时删除最久没有使用的
+/// ```
+/// use std::time::Duration;
+/// use osu_api_rs::http::{CacheBackend, MemoryCache};
+///
+/// let cache = MemoryCache::new(2);
+/// cache.set("a", "1".to_string(), Duration::from_secs(60));
+/// cache.set("b", "2".to_string(), Duration::from_secs(60));
+/// assert_eq!(cache.get("a").as_deref(), Some("1"));
+/// // 超过容量, 删除最久没有使用的 "b"
+/// cache.set("c", "3".to_string(), Duration::from_secs(60));
+/// assert_eq!(cache.get("b"), None);
+/// assert_eq!(cache.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// key -> (值, 过期时间, 最后使用的序号)
+    entries: HashMap<String, (String, Instant, u64)>,
+    /// 最后使用的序号 -> key
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) -> u64 {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.2);
+            entry.2 = tick;
+            self.order.insert(tick, key.to_string());
+        }
+        tick
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((_, _, tick)) = self.entries.remove(key) {
+            self.order.remove(&tick);
+        }
+    }
+}
+
+impl MemoryCache {
+    /// * capacity - 最多缓存多少个响应
+    pub fn new(capacity: usize) -> MemoryCache {
+        MemoryCache { capacity: capacity.max(1), inner: Mutex::new(Lru::default()) }
+    }
+    /// 当前缓存的数量 (包括已经过期但还没删除的)
+    pub fn len(&self) -> usize {
+        self.lru().entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lru(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let mut lru = self.lru();
+        let (value, expires, _) = lru.entries.get(key)?;
+        if *expires <= Instant::now() {
+            lru.remove(key);
+            return None;
+        }
+        let value = value.clone();
+        lru.touch(key);
+        Some(value)
+    }
+
+    fn set(&self, key: &str, value: String, ttl: Duration) {
+        let mut lru = self.lru();
+        lru.remove(key);
+        while lru.entries.len() >= self.capacity {
+            let oldest = match lru.order.iter().next() {
+                Some((_, key)) => key.clone(),
+                None => break,
+            };
+            lru.remove(&oldest);
+        }
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.entries.insert(key.to_string(), (value, Instant::now() + ttl, tick));
+        lru.order.insert(tick, key.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        self.lru().remove(key);
+    }
+}
+
+/// # Cache
+/// 按接口设置缓存时间, 没有设置的接口不缓存。
+///
+/// 接口名: v1 为 `get_beatmaps` 这样的名字, v2 为 `beatmaps/lookup`, `beatmaps/{beatmap}/scores` 这样的路径。
+/// ```
+/// use std::time::Duration;
+/// use osu_api_rs::ApiV1;
+/// use osu_api_rs::http::Cache;
+/// # const API_KEY: &str = "API_KEY";
+///
+/// let cache = Cache::memory(10_000)
+///     .ttl("get_beatmaps", Duration::from_secs(24 * 60 * 60))
+///     .ttl("get_user_recent", Duration::from_secs(30));
+/// let api_v1 = ApiV1::new(API_KEY).with_cache(cache);
+/// assert!(api_v1.cache().is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cache {
+    backend: Arc<dyn CacheBackend>,
+    /// 接口名 -> 缓存时间
+    ttls: HashMap<String, Duration>,
+    /// 没有单独设置的接口使用的缓存时间, None 则不缓存
+    default_ttl: Option<Duration>,
+}
+
+impl Cache {
+    pub fn new(backend: impl CacheBackend + 'static) -> Cache {
+        Cache { backend: Arc::new(backend), ttls: HashMap::new(), default_ttl: None }
+    }
+    /// 使用 [`MemoryCache`]
+    pub fn memory(capacity: usize) -> Cache {
+        Cache::new(MemoryCache::new(capacity))
+    }
+    /// 使用 [`MemoryCache`], 并设置推荐的缓存时间:
+    /// * 谱面信息 (`get_beatmaps`, `beatmaps/lookup`) - 1 天
+    /// * 回放 (`get_replay`) - 1 天
+    /// * 玩家信息, 成绩, BP - 5 分钟
+    /// * 最近游玩, mp 房间 - 30 秒
+    pub fn recommended(capacity: usize) -> Cache {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let minutes = Duration::from_secs(5 * 60);
+        let seconds = Duration::from_secs(30);
+        Cache::memory(capacity)
+            .ttl("get_beatmaps", day)
+            .ttl("get_replay", day)
+            .ttl("get_user", minutes)
+            .ttl("get_scores", minutes)
+            .ttl("get_user_best", minutes)
+            .ttl("get_user_recent", seconds)
+            .ttl("get_match", seconds)
+            .ttl("beatmaps/lookup", day)
+            .ttl("beatmaps/{beatmap}/scores", minutes)
+            .ttl("beatmaps/{beatmap}/scores/users/{user}", minutes)
+            .ttl("beatmaps/{beatmap}/scores/users/{user}/all", minutes)
+            .ttl("beatmapsets/search", minutes)
+            .ttl("users/{user}/{mode}", minutes)
+            .ttl("users", minutes)
+            .ttl("users/{user}/scores/{type}", seconds)
+            .ttl("users/{user}/beatmapsets/{type}", minutes)
+            .ttl("users/{user}/beatmapsets/most_played", minutes)
+            .ttl("users/{user}/recent_activity", seconds)
+    }
+    /// 设置某个接口的缓存时间
+    pub fn ttl(mut self, endpoint: impl Into<String>, ttl: Duration) -> Cache {
+        self.ttls.insert(endpoint.into(), ttl);
+        self
+    }
+    /// 不缓存某个接口
+    pub fn no_cache(mut self, endpoint: &str) -> Cache {
+        self.ttls.insert(endpoint.to_string(), Duration::ZERO);
+        self
+    }
+    /// 没有单独设置的接口使用的缓存时间
+    pub fn default_ttl(mut self, ttl: Duration) -> Cache {
+        self.default_ttl = Some(ttl);
+        self
+    }
+    /// 缓存的存储
+    pub fn backend(&self) -> &dyn CacheBackend {
+        self.backend.as_ref()
+    }
+
+    pub(crate) fn ttl_of(&self, endpoint: &str) -> Option<Duration> {
+        self.ttls.get(endpoint).copied()
+            .or(self.default_ttl)
+            .filter(|ttl| !ttl.is_zero())
+    }
+
+    /// 协议 + host + 端口 + 路径 + 排序后的查询参数 (去掉 api key), 有 access token 时加上它的哈希
+    pub(crate) fn key(url: &Url, access_token: Option<&str>) -> String {
+        let mut query = url.query_pairs()
+            .filter(|(k, _)| k != "k")
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>();
+        query.sort();
+        let mut key = format!("{}://{}:{}{}?{}",
+                              url.scheme(),
+                              url.host_str().unwrap_or_default(),
+                              url.port_or_known_default().unwrap_or_default(),
+                              url.path(),
+                              query.join("&"));
+        if let Some(token) = access_token {
+            // 不保存 token 本身
+            let mut hasher = DefaultHasher::new();
+            token.hash(&mut hasher);
+            key.push_str(&format!("#{:016x}", hasher.finish()));
+        }
+        key
+    }
+}
@@ -0,0 +1,190 @@
+//! 令牌桶限流
+//!
+//! osu! api v1 的限制: 每分钟1200次，最高瞬时1400次。
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// # RateLimiter
+/// 令牌桶限流器, clone 出来的限流器共用同一个桶。
+///
+/// 没有令牌时请求不会失败, 而是排队等待 (先到先得)。
+/// ```
+/// use osu_api_rs::ApiV1;
+/// use osu_api_rs::http::RateLimiter;
+/// # const API_KEY: &str = "API_KEY";
+///
+/// let api_v1 = ApiV1::new(API_KEY).with_rate_limiter(RateLimiter::v1());
+/// // 还能发多少个请求
+/// let budget = api_v1.rate_limiter().unwrap().available();
+/// assert_eq!(budget, 1400);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// 桶的容量 (瞬时最多请求数)
+    burst: u32,
+    /// 每分钟补充的令牌数
+    per_minute: u32,
+    bucket: Mutex<Bucket>,
+    /// 等待中的请求排队用
+    queue: tokio::sync::Mutex<()>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// * per_minute - 每分钟补充的令牌数
+    /// * burst - 桶的容量, 初始是满的
+    pub fn new(per_minute: u32, burst: u32) -> RateLimiter {
+        let per_minute = per_minute.max(1);
+        let burst = burst.max(1);
+        RateLimiter {
+            inner: Arc::new(Inner {
+                burst,
+                per_minute,
+                bucket: Mutex::new(Bucket { tokens: burst as f64, last: Instant::now() }),
+                queue: tokio::sync::Mutex::new(()),
+            })
+        }
+    }
+    /// api v1 的限制: 每分钟1200次，最高瞬时1400次
+    pub fn v1() -> RateLimiter {
+        RateLimiter::new(1200, 1400)
+    }
+    /// 每分钟补充的令牌数
+    pub fn per_minute(&self) -> u32 {
+        self.inner.per_minute
+    }
+    /// 桶的容量
+    pub fn burst(&self) -> u32 {
+        self.inner.burst
+    }
+    /// 当前剩余的令牌数 (现在最多还能立即发送多少个请求)
+    pub fn available(&self) -> u32 {
+        let mut bucket = self.bucket();
+        self.refill(&mut bucket);
+        bucket.tokens as u32
+    }
+    /// 距离下一个令牌可用还需要等待的时间, 有令牌时为 0
+    pub fn wait_time(&self) -> Duration {
+        let mut bucket = self.bucket();
+        self.refill(&mut bucket);
+        self.time_until_token(&bucket)
+    }
+    /// 尝试取一个令牌, 没有令牌时直接返回 false
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket();
+        self.refill(&mut bucket);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+    /// 取一个令牌, 没有令牌时排队等待
+    pub async fn acquire(&self) {
+        let _queue = self.inner.queue.lock().await;
+        loop {
+            let wait = {
+                let mut bucket = self.bucket();
+                self.refill(&mut bucket);
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                self.time_until_token(&bucket)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn bucket(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.inner.bucket.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second()).min(self.inner.burst as f64);
+        bucket.last = now;
+    }
+
+    fn time_until_token(&self, bucket: &Bucket) -> Duration {
+        if bucket.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - bucket.tokens) / self.per_second())
+        }
+    }
+
+    fn per_second(&self) -> f64 {
+        self.inner.per_minute as f64 / 60.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn burst() {
+        let limiter = RateLimiter::new(60, 5);
+        assert_eq!(limiter.available(), 5);
+        for _ in 0..5 {
+            assert!(limiter.try_acquire());
+        }
+        assert!(!limiter.try_acquire());
+        assert_eq!(limiter.available(), 0);
+        assert_eq!(limiter.wait_time(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refill() {
+        // 每秒补充一个令牌
+        let limiter = RateLimiter::new(60, 5);
+        while limiter.try_acquire() {}
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert!(!limiter.try_acquire());
+        assert_eq!(limiter.wait_time(), Duration::from_millis(500));
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        // 补充不会超过桶的容量
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(limiter.available(), 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_waits_for_refill() {
+        let limiter = RateLimiter::new(120, 2);
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        // 之后每 500ms 一个
+        for i in 1..=3 {
+            limiter.acquire().await;
+            assert_eq!(start.elapsed(), Duration::from_millis(500 * i));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn clones_share_bucket() {
+        let limiter = RateLimiter::new(60, 2);
+        let other = limiter.clone();
+        assert!(limiter.try_acquire());
+        assert!(other.try_acquire());
+        assert!(!limiter.try_acquire());
+    }
+}
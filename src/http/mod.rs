@@ -0,0 +1,288 @@
+//! HTTP 客户端配置
+//!
+//! `ApiV1` 与 `ApiV2` 内部持有同一个 [`reqwest::Client`],所有请求复用连接池。
+//!
+//! `reqwest::Client` 内部是 `Arc`,clone 的开销很小,可以放心在多个 tokio 任务之间传递。
+//!
+//! 发送请求的方式可以通过 [`HttpTransport`] 替换, 测试时使用 [`FakeTransport`]。
+
+mod cache;
+mod limiter;
+mod retry;
+mod transport;
+
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::{Client, Method, Proxy, StatusCode, Url};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE, RETRY_AFTER};
+use serde_json::Value;
+use crate::error::{Error, Result};
+
+pub use cache::{Cache, CacheBackend, MemoryCache};
+pub use limiter::RateLimiter;
+pub use retry::RetryPolicy;
+pub use transport::{BoxFuture, FakeTransport, HttpRequest, HttpResponse, HttpTransport, ReqwestTransport};
+
+/// 默认 User-Agent
+pub static USER_AGENT: &str = concat!("osu-api-rs/", env!("CARGO_PKG_VERSION"));
+
+/// # ClientBuilder
+/// 配置 `ApiV1` / `ApiV2` 使用的 HTTP 客户端
+/// ```
+/// use std::time::Duration;
+/// use osu_api_rs::ApiV1;
+/// use osu_api_rs::http::ClientBuilder;
+/// # const API_KEY: &str = "API_KEY";
+///
+/// let client = ClientBuilder::new()
+///     .timeout(Duration::from_secs(10))
+///     .user_agent("my-bot/1.0")
+///     .pool_max_idle_per_host(8)
+///     .build()
+///     .unwrap();
+/// let api_v1 = ApiV1::with_client(API_KEY, client);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    /// 整个请求的超时时间,默认 30 秒
+    timeout: Option<Duration>,
+    /// 建立连接的超时时间
+    connect_timeout: Option<Duration>,
+    user_agent: String,
+    proxy: Option<Proxy>,
+    /// 每个 host 最多保留的空闲连接数
+    pool_max_idle_per_host: Option<usize>,
+    /// 每个请求都会带上的请求头
+    default_headers: HeaderMap,
+}
+
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder {
+            timeout: Some(Duration::from_secs(30)),
+            connect_timeout: None,
+            user_agent: USER_AGENT.to_string(),
+            proxy: None,
+            pool_max_idle_per_host: None,
+            default_headers: HeaderMap::new(),
+        }
+    }
+    /// 请求超时时间
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// 不设置超时
+    pub fn no_timeout(mut self) -> ClientBuilder {
+        self.timeout = None;
+        self
+    }
+    /// 连接超时时间
+    pub fn connect_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> ClientBuilder {
+        self.user_agent = user_agent.into();
+        self
+    }
+    /// 代理, 例: `Proxy::all("socks5://127.0.0.1:1080")`
+    pub fn proxy(mut self, proxy: Proxy) -> ClientBuilder {
+        self.proxy = Some(proxy);
+        self
+    }
+    /// 连接池大小 (每个 host 的最大空闲连接数)
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> ClientBuilder {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+    /// 添加一个默认请求头
+    pub fn default_header(mut self, key: HeaderName, value: HeaderValue) -> ClientBuilder {
+        self.default_headers.insert(key, value);
+        self
+    }
+    /// 添加多个默认请求头
+    pub fn default_headers(mut self, headers: HeaderMap) -> ClientBuilder {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    pub fn build(self) -> reqwest::Result<Client> {
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent)
+            .default_headers(self.default_headers);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        builder.build()
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder::new()
+    }
+}
+
+/// `ApiV1` / `ApiV2` 共用的请求封装
+#[derive(Debug, Clone)]
+pub(crate) struct Http {
+    transport: Arc<dyn HttpTransport>,
+    /// 限流器, 默认不限流
+    limiter: Option<RateLimiter>,
+    /// 重试策略
+    retry: RetryPolicy,
+    /// 响应缓存, 默认不缓存
+    cache: Option<Cache>,
+}
+
+impl Http {
+    pub(crate) fn new(transport: impl HttpTransport + 'static) -> Http {
+        Http { transport: Arc::new(transport), limiter: None, retry: RetryPolicy::default(), cache: None }
+    }
+
+    pub(crate) fn set_rate_limiter(&mut self, limiter: Option<RateLimiter>) {
+        self.limiter = limiter;
+    }
+
+    pub(crate) fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.limiter.as_ref()
+    }
+
+    pub(crate) fn set_retry(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    pub(crate) fn retry(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    pub(crate) fn set_cache(&mut self, cache: Option<Cache>) {
+        self.cache = cache;
+    }
+
+    pub(crate) fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    /// * endpoint - 接口名, 用来查找缓存时间
+    /// * parse - 解析响应, 解析成功的才会缓存
+    pub(crate) async fn get<T>(&self, endpoint: &str, url: Url, access_token: Option<&str>, parse: impl Fn(String) -> Result<T>) -> Result<T> {
+        let cache = self.cache.as_ref()
+            .and_then(|cache| Some((cache, cache.ttl_of(endpoint)?, Cache::key(&url, access_token))));
+        if let Some((cache, _, key)) = &cache {
+            if let Some(body) = cache.backend().get(key) {
+                return parse(body);
+            }
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        bearer_auth(&mut headers, access_token)?;
+        let body = self.send(HttpRequest { method: Method::GET, url, headers, body: None }).await?;
+        match &cache {
+            Some((cache, ttl, key)) => {
+                let data = parse(body.clone())?;
+                cache.backend().set(key, body, *ttl);
+                Ok(data)
+            }
+            None => parse(body),
+        }
+    }
+
+    pub(crate) async fn post(&self, url: &str, json: &Value, access_token: Option<&str>) -> Result<String> {
+        let url = Url::parse(url).map_err(|err| Error::InvalidInput(err.to_string()))?;
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        bearer_auth(&mut headers, access_token)?;
+        let body = serde_json::to_vec(json)?;
+        self.send(HttpRequest { method: Method::POST, url, headers, body: Some(body) }).await
+    }
+
+    /// 限流 + 重试
+    async fn send(&self, request: HttpRequest) -> Result<String> {
+        let idempotent = request.method == Method::GET;
+        let mut attempt = 1;
+        loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire().await;
+            }
+            let result = match self.transport.send(request.clone()).await {
+                Ok(response) => read_response(response),
+                Err(err) => Err(err),
+            };
+            let err = match result {
+                Ok(body) => return Ok(body),
+                Err(err) => err,
+            };
+            match self.retry.delay(&err, attempt, idempotent) {
+                Some(delay) => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return Err(err),
+            }
+        }
+    }
+}
+
+fn bearer_auth(headers: &mut HeaderMap, access_token: Option<&str>) -> Result<()> {
+    if let Some(token) = access_token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|err| Error::InvalidInput(err.to_string()))?;
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    Ok(())
+}
+
+/// 检查状态码, 非 2xx 转换为对应的 [`Error`]
+///
+/// 401 / 429 有单独的错误, 其他的状态码保留在 [`Error::Status`] 中 (重试需要), 有 `{"error": "..."}` 时 body 为其中的信息
+fn read_response(response: HttpResponse) -> Result<String> {
+    let HttpResponse { status, headers, body } = response;
+    let retry_after = retry_after(&headers);
+    if status.is_success() {
+        return Ok(body);
+    }
+    let body = api_error(&body).unwrap_or(body);
+    Err(match status {
+        StatusCode::UNAUTHORIZED => Error::Unauthorized(body),
+        StatusCode::TOO_MANY_REQUESTS => Error::RateLimited { retry_after },
+        _ => Error::Status { status: status.as_u16(), body },
+    })
+}
+
+/// 响应头 `Retry-After` (秒)
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers.get(RETRY_AFTER)?
+        .to_str().ok()?
+        .trim().parse::<u64>().ok()
+        .map(Duration::from_secs)
+}
+
+/// 取出 osu! 的 `{"error": "..."}`
+pub(crate) fn api_error(body: &str) -> Option<String> {
+    let json = serde_json::from_str::<Value>(body).ok()?;
+    json.get("error")?.as_str().map(|s| s.to_string())
+}
+
+impl Default for Http {
+    fn default() -> Self {
+        Http::new(ReqwestTransport::new(default_client()))
+    }
+}
+
+/// 使用默认配置构建的客户端
+pub(crate) fn default_client() -> Client {
+    ClientBuilder::new().build().unwrap_or_default()
+}
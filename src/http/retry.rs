@@ -0,0 +1,109 @@
+//! 失败重试
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use crate::error::Error;
+
+/// # RetryPolicy
+/// 遇到 429 或者 5xx 时自动重试, 等待时间指数增长并加上随机抖动,
+/// 如果响应头里有 `Retry-After` 则按照它来等待。
+///
+/// 默认只重试 GET 请求 (幂等), 最多尝试 3 次。
+/// ```
+/// use std::time::Duration;
+/// use osu_api_rs::ApiV1;
+/// use osu_api_rs::http::RetryPolicy;
+/// # const API_KEY: &str = "API_KEY";
+///
+/// let api_v1 = ApiV1::new(API_KEY)
+///     .with_retry(RetryPolicy::new().max_attempts(5).base_delay(Duration::from_secs(1)));
+/// // 单次调用不重试: api_v1.clone().with_retry(RetryPolicy::none()).get_beatmap(Some(252002)).await
+/// let once = api_v1.clone().with_retry(RetryPolicy::none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最多尝试的次数 (包括第一次), 1 表示不重试
+    max_attempts: u32,
+    /// 第一次重试前的等待时间, 之后每次翻倍
+    base_delay: Duration,
+    /// 最长等待时间
+    max_delay: Duration,
+    /// 是否重试非幂等的请求 (POST)
+    retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            retry_non_idempotent: false,
+        }
+    }
+    /// 不重试
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::new().max_attempts(1)
+    }
+    /// 最多尝试的次数 (包括第一次)
+    pub fn max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+    /// 第一次重试前的等待时间
+    pub fn base_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.base_delay = delay;
+        self
+    }
+    /// 最长等待时间
+    pub fn max_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.max_delay = delay;
+        self
+    }
+    /// 是否也重试 POST 请求
+    pub fn retry_non_idempotent(mut self, retry: bool) -> RetryPolicy {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// 第 `attempt` 次请求失败后是否重试, 返回需要等待的时间
+    pub(crate) fn delay(&self, err: &Error, attempt: u32, idempotent: bool) -> Option<Duration> {
+        if attempt >= self.max_attempts || !(idempotent || self.retry_non_idempotent) {
+            return None;
+        }
+        match err.status()? {
+            429 => match err {
+                Error::RateLimited { retry_after: Some(after) } => Some(*after),
+                _ => Some(self.backoff(attempt)),
+            },
+            status if status >= 500 => Some(self.backoff(attempt)),
+            _ => None,
+        }
+    }
+
+    /// 指数退避, 在 [delay/2, delay] 之间随机
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+        let half = delay / 2;
+        half + half.mul_f64(jitter())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+/// [0, 1) 之间的随机数
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
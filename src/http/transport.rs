@@ -0,0 +1,161 @@
+//! 发送请求的方式
+//!
+//! 默认使用 [`ReqwestTransport`], 测试时可以换成 [`FakeTransport`], 不需要联网。
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use reqwest::{Client, Method, StatusCode, Url};
+use reqwest::header::HeaderMap;
+use crate::error::Result;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// 一个 HTTP 请求
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+/// 一个 HTTP 响应
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> HttpResponse {
+        HttpResponse { status, headers: HeaderMap::new(), body: body.into() }
+    }
+    /// 200 OK
+    pub fn ok(body: impl Into<String>) -> HttpResponse {
+        HttpResponse::new(StatusCode::OK, body)
+    }
+}
+
+/// # HttpTransport
+/// 请求进, 状态码 + 响应头 + 响应内容出。
+///
+/// 限流, 重试都在上层处理, 这里只负责把请求发出去。
+pub trait HttpTransport: Debug + Send + Sync {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>>;
+}
+
+/// 使用 reqwest 发送请求 (默认)
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> ReqwestTransport {
+        ReqwestTransport { client }
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let mut req = self.client.request(request.method, request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                req = req.body(body);
+            }
+            let response = req.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.text().await?;
+            Ok(HttpResponse { status, headers, body })
+        })
+    }
+}
+
+/// # FakeTransport
+/// 内存中的假服务器, 用于测试。
+///
+/// 按 请求方法 + 路径 匹配预先设置的响应, 没有匹配的返回 404。
+/// 同一个路由设置了多个响应时按顺序返回, 最后一个会一直重复。
+///
+/// clone 出来的 FakeTransport 共用同一份数据。
+/// ```
+/// # #[tokio::main]
+/// # async fn main() -> osu_api_rs::error::Result<()> {
+/// use osu_api_rs::ApiV1;
+/// use osu_api_rs::http::{FakeTransport, HttpResponse};
+///
+/// let fake = FakeTransport::new()
+///     .on_get("/api/get_match", HttpResponse::ok(r#"{
+///         "match": {"match_id": "1", "name": "OWC: (China) vs (Japan)", "start_time": "2022-01-01 00:00:00", "end_time": null},
+///         "games": []
+///     }"#));
+/// let api_v1 = ApiV1::with_transport("key", fake.clone());
+/// let room = api_v1.get_match(Some(1)).await?;
+/// assert_eq!(room.room_info.name, "OWC: (China) vs (Japan)");
+/// assert_eq!(fake.requests().len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FakeTransport {
+    inner: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    routes: Vec<(Method, String, VecDeque<HttpResponse>)>,
+    requests: Vec<HttpRequest>,
+}
+
+impl FakeTransport {
+    pub fn new() -> FakeTransport {
+        FakeTransport::default()
+    }
+    /// 设置一个响应, `path` 为 url 的路径 (例: `/api/get_user`, `/api/v2/beatmaps/lookup`)
+    pub fn on(self, method: Method, path: impl Into<String>, response: HttpResponse) -> FakeTransport {
+        {
+            let mut state = self.state();
+            let path = path.into();
+            match state.routes.iter_mut().find(|(m, p, _)| *m == method && *p == path) {
+                Some((_, _, responses)) => responses.push_back(response),
+                None => state.routes.push((method, path, VecDeque::from([response]))),
+            }
+        }
+        self
+    }
+    pub fn on_get(self, path: impl Into<String>, response: HttpResponse) -> FakeTransport {
+        self.on(Method::GET, path, response)
+    }
+    pub fn on_post(self, path: impl Into<String>, response: HttpResponse) -> FakeTransport {
+        self.on(Method::POST, path, response)
+    }
+    /// 收到过的所有请求
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl HttpTransport for FakeTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        let mut state = self.state();
+        let route = state.routes.iter_mut()
+            .find(|(m, p, _)| *m == request.method && p == request.url.path());
+        let response = match route {
+            Some((_, _, responses)) if responses.len() > 1 => responses.pop_front(),
+            Some((_, _, responses)) => responses.front().cloned(),
+            None => None,
+        }.unwrap_or_else(|| HttpResponse::new(StatusCode::NOT_FOUND, r#"{"error":"no route"}"#));
+        state.requests.push(request);
+        Box::pin(async move { Ok(response) })
+    }
+}
@@ -5,23 +5,28 @@
 mod v1;
 mod v2;
 mod util;
+#[allow(dead_code)]
 mod private;
 pub mod error;
+pub mod http;
 
 pub use v1::{
     entity as entity_v1,
+    request as request_v1,
     api::{
         ApiV1
     }
 };
 
 pub use util::{
-    UserType,Mods,Mode
+    UserType,Mods,GameMod,ModSettings,Mode,RankStatus,Genre,Language
 };
 
 pub use v2::{
     entity as entity_v2,
+    request as request_v2,
+    oauth as oauth_v2,
     api::{
-        ApiV2
+        ApiV2, ApiV2Builder
     }
 };
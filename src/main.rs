@@ -1,7 +1,7 @@
-use osu_api_rs::api::{ApiV1, UserType};
+use osu_api_rs::{ApiV1, Mode, UserType};
 #[tokio::main]
 async fn main() {
-    let v1 = ApiV1::new(format!("{}", "10fe4f3782bb425e2d2c0ba8a44628906eb1ba7a"));
-    let bp = v1.get_user_bp(UserType::USERID(18267600), Some(3),2).await;
+    let v1 = ApiV1::new("10fe4f3782bb425e2d2c0ba8a44628906eb1ba7a");
+    let _bp = v1.get_user_bp(UserType::USERID(18267600), Some(Mode::Mania),2).await;
 
 }
\ No newline at end of file
@@ -6,12 +6,12 @@
 //!
 //! 数据可能不准确,本库使用的 https://osu.ppy.sb  的api数据
 
-/// ``` rust
+/// ```ignore
 /// //一般使用默认就行了
 /// let api =  Api::default();
 /// ```
 ///
-/// ```
+/// ```ignore
 /// //想用需要密匙的功能就:
 /// let api =  Api::new(API_KEY);
 /// ```
@@ -0,0 +1,86 @@
+//! 反序列化辅助函数
+//!
+//! v1 返回的数字/布尔值都是字符串 (`"1"`, `"5.59516"`), 这里统一兼容字符串和原生类型。
+//!
+//! v1 的时间为 MySQL 格式 (`2013-07-02 01:01:12`) 且不带时区, 都是 UTC
+//! (见 https://github.com/ppy/osu-api/wiki, 中文 wiki 中写的 UTC+8 是旧版接口的说法)。
+
+use std::fmt::Display;
+use std::str::FromStr;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use serde_json::Value;
+
+/// 把 字符串/数字/布尔值 统一转换成字符串, null 和 "" 返回 None
+fn value_to_string(value: Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) if s.is_empty() => None,
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// `"123"` / `123` -> 123
+pub fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where D: Deserializer<'de>, T: FromStr, T::Err: Display {
+    match option_from_str(deserializer)? {
+        Some(value) => Ok(value),
+        None => Err(D::Error::custom("值为空")),
+    }
+}
+
+/// 同 [`from_str`], null 和 `""` 为 None
+pub fn option_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where D: Deserializer<'de>, T: FromStr, T::Err: Display {
+    match value_to_string(Value::deserialize(deserializer)?) {
+        Some(s) => s.trim().parse::<T>().map(Some).map_err(D::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// `"0"` / `"1"` / `0` / `1` / `false` / `true` -> bool
+pub fn bool_from_str<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where D: Deserializer<'de> {
+    match value_to_string(Value::deserialize(deserializer)?).as_deref() {
+        Some("1") | Some("true") => Ok(true),
+        Some("0") | Some("false") | None => Ok(false),
+        Some(other) => Err(D::Error::custom(format!("不是布尔值: {}", other))),
+    }
+}
+
+/// MySQL 的日期格式
+pub static MYSQL_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// UTC -> v1 的 MySQL 格式, 用于 `since` 等参数
+pub fn to_mysql(date: &DateTime<Utc>) -> String {
+    date.format(MYSQL_DATE_FORMAT).to_string()
+}
+
+/// 解析 v1 的时间, 也兼容序列化后的 RFC 3339 格式
+fn parse_v1_datetime(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    NaiveDateTime::parse_from_str(s, MYSQL_DATE_FORMAT)
+        .map(|date| Utc.from_utc_datetime(&date))
+        .or_else(|_| DateTime::parse_from_rfc3339(s).map(|date| date.with_timezone(&Utc)))
+}
+
+/// `"2013-07-02 01:01:12"` -> `DateTime<Utc>`
+pub fn datetime<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where D: Deserializer<'de> {
+    match option_datetime(deserializer)? {
+        Some(date) => Ok(date),
+        None => Err(D::Error::custom("日期为空")),
+    }
+}
+
+/// 同 [`datetime`], null 和 `""` 为 None
+pub fn option_datetime<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where D: Deserializer<'de> {
+    match value_to_string(Value::deserialize(deserializer)?) {
+        Some(s) => parse_v1_datetime(&s).map(Some).map_err(D::Error::custom),
+        None => Ok(None),
+    }
+}
@@ -1,11 +1,15 @@
+use super::Mods;
+
 
 /// UserType 枚举
+#[derive(Debug, Clone, Copy)]
 pub enum UserType<'a> {
     /// user id
     ///
+    /// ```text
     /// https://osu.ppy.sh/users/18267600
-    ///
-    ///                             ^
+    ///                          ^
+    /// ```
     USERID(i64),
     /// user name
     /// <h3>osu 用户名</h3>
@@ -16,55 +20,76 @@ pub enum UserType<'a> {
 /// 直接定义一个枚举(
 pub enum DataType<'a> {
     Int64(Option<i64>),
-    Int32(Option<i32>),
     Int16(Option<i16>),
     Int8(Option<i8>),
     Mode(Option<Mode>,bool),
     String(Option<&'a str>),
-    Mods(Option<Vec<Mods>>),
-}
-/// osu Mods
-pub enum Mods{
-    NONE,
-    NF,
-    EZ,
-    MR,
-    NV,
-    HD,
-    HR,
-    SD,
-    DT,
-    RX,
-    HT,
-    NC,
-    FL,
-    AUTO,
-    SO,
-    AP,
-    PF,
-    Key1,
-    Key2,
-    Key3,
-    Key4,
-    Key5,
-    Key6,
-    Key7,
-    Key8,
-    Key9,
-    Key10,
-    FadeIn,
-    Random,
-    Cinema,
-    Coop,
-    V2,
-    LM,
-    KeyMod,
-    FreeModAllowed,
+    /// true: 每个 mod 的缩写作为一个参数 (v2 的 `mods[]`), false: 数字 (v1)
+    Mods(Option<Mods>,bool),
 }
 /// osu mode
+///
+/// 0 = osu!, 1 = taiko, 2 = catch (fruits), 3 = mania
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Mode{
-    Fruits,
-    Mania,
-    Osu,
-    Taiko,
+    Fruits = 2,
+    Mania = 3,
+    Osu = 0,
+    Taiko = 1,
+}
+/// 谱面状态
+///
+/// 4 = loved, 3 = qualified, 2 = approved, 1 = ranked, 0 = pending, -1 = WIP, -2 = graveyard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RankStatus {
+    Graveyard = -2,
+    Wip = -1,
+    Pending = 0,
+    Ranked = 1,
+    Approved = 2,
+    Qualified = 3,
+    Loved = 4,
+}
+/// 谱面曲风
+///
+/// 0 = any, 1 = unspecified, 2 = video game, 3 = anime, 4 = rock, 5 = pop, 6 = other, 7 = novelty,
+/// 9 = hip hop, 10 = electronic, 11 = metal, 12 = classical, 13 = folk, 14 = jazz (没有 8)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Genre {
+    Any = 0,
+    Unspecified = 1,
+    VideoGame = 2,
+    Anime = 3,
+    Rock = 4,
+    Pop = 5,
+    Other = 6,
+    Novelty = 7,
+    HipHop = 9,
+    Electronic = 10,
+    Metal = 11,
+    Classical = 12,
+    Folk = 13,
+    Jazz = 14,
+}
+/// 谱面语言
+///
+/// 0 = any, 1 = other, 2 = english, 3 = japanese, 4 = chinese, 5 = instrumental, 6 = korean, 7 = french,
+/// 8 = german, 9 = swedish, 10 = spanish, 11 = italian, 12 = russian, 13 = polish, 14 = unspecified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Any = 0,
+    Other = 1,
+    English = 2,
+    Japanese = 3,
+    Chinese = 4,
+    Instrumental = 5,
+    Korean = 6,
+    French = 7,
+    German = 8,
+    Swedish = 9,
+    Spanish = 10,
+    Italian = 11,
+    Russian = 12,
+    Polish = 13,
+    Unspecified = 14,
 }
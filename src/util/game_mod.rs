@@ -0,0 +1,207 @@
+//! lazer 的 mod
+//!
+//! v2 新的接口返回的 mod 为对象, 例: `{"acronym":"DT","settings":{"speed_change":1.3}}`,
+//! 旧的接口返回的是字符串 `"DT"`, 这里都兼容。
+//!
+//! 引用: https://osu.ppy.sh/docs/index.html#mod
+
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error as _;
+use serde_json::Value;
+use super::Mods;
+
+/// lazer 中没有对应旧版位的 mod, 但旧版成绩在 lazer 中都带着它
+const CLASSIC: &str = "CL";
+/// lazer 中 ScoreV2 的缩写
+const SCORE_V2: &str = "SV2";
+
+/// 一个 lazer mod
+/// ```
+/// use osu_api_rs::{GameMod, Mods};
+///
+/// let mods: Vec<GameMod> = serde_json::from_str(r#"["HD", {"acronym":"DT"}]"#).unwrap();
+/// assert_eq!(GameMod::to_legacy(&mods), Some(Mods::HD | Mods::DT));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameMod {
+    /// 缩写, 例: `DT`
+    pub acronym: String,
+    #[serde(skip_serializing_if = "ModSettings::is_empty")]
+    pub settings: ModSettings,
+}
+
+/// mod 的设置, 常用的设置有对应的字段, 其他的放在 [`extra`](ModSettings::extra) 中
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModSettings {
+    /// DT / NC / HT / DC 的速度, 例: 1.5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_change: Option<f64>,
+    /// DT / HT 是否改变音调
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjust_pitch: Option<bool>,
+    /// DA (Difficulty Adjust)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circle_size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approach_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overall_difficulty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extended_limits: Option<bool>,
+    /// FL 的大小
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub combo_based_size: Option<bool>,
+    /// RD 的随机种子
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// SD / PF 失败时是否重新开始
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<bool>,
+    /// 其他的设置
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl ModSettings {
+    /// 没有任何设置, 即使用默认值
+    pub fn is_empty(&self) -> bool {
+        *self == ModSettings::default()
+    }
+}
+
+impl GameMod {
+    /// 没有设置的 mod
+    pub fn new(acronym: impl Into<String>) -> GameMod {
+        GameMod { acronym: acronym.into().to_ascii_uppercase(), settings: ModSettings::default() }
+    }
+    pub fn with_settings(mut self, settings: ModSettings) -> GameMod {
+        self.settings = settings;
+        self
+    }
+    /// 转换为旧版的 mod, 没有对应的位或者有设置 (无法无损转换) 时返回 None
+    ///
+    /// `CL` (Classic) 返回 [`Mods::NONE`], 旧版成绩本来就是 classic 的
+    ///
+    /// 只认 lazer 的缩写, 旧版的其他写法在 lazer 中可能是别的 mod (例: lazer 的 `CO` 是 mania 的 Cover, 不是 KeyCoop)
+    pub fn legacy(&self) -> Option<Mods> {
+        if !self.settings.is_empty() {
+            return None;
+        }
+        match self.acronym.as_str() {
+            CLASSIC => Some(Mods::NONE),
+            SCORE_V2 => Some(Mods::V2),
+            acronym => Mods::from_canonical_acronym(acronym),
+        }
+    }
+    /// 转换为旧版的 mods, 其中有一个无法无损转换时返回 None
+    ///
+    /// 同旧版客户端, NC / PF 会带上 DT / SD (见 [`Mods::with_implied`])
+    pub fn to_legacy(mods: &[GameMod]) -> Option<Mods> {
+        mods.iter().map(GameMod::legacy).collect::<Option<Mods>>().map(|mods| mods.with_implied())
+    }
+    /// 旧版的 mods -> lazer mod, NC / PF 不会再带上 DT / SD
+    pub fn from_legacy(mods: Mods) -> Vec<GameMod> {
+        mods.acronyms().into_iter().map(|acronym| match acronym {
+            "V2" => GameMod::new(SCORE_V2),
+            acronym => GameMod::new(acronym),
+        }).collect()
+    }
+}
+
+impl From<&str> for GameMod {
+    fn from(acronym: &str) -> GameMod {
+        GameMod::new(acronym)
+    }
+}
+
+impl fmt::Display for GameMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.acronym)?;
+        if let Some(speed) = self.settings.speed_change {
+            write!(f, "({}x)", speed)?;
+        }
+        Ok(())
+    }
+}
+
+/// 兼容 `"DT"` 和 `{"acronym":"DT","settings":{...}}`
+impl<'de> Deserialize<'de> for GameMod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Object {
+            acronym: String,
+            #[serde(default)]
+            settings: Option<ModSettings>,
+        }
+        match Value::deserialize(deserializer)? {
+            Value::String(acronym) => Ok(GameMod::new(acronym)),
+            value @ Value::Object(_) => {
+                let object = Object::deserialize(value).map_err(D::Error::custom)?;
+                Ok(GameMod::new(object.acronym).with_settings(object.settings.unwrap_or_default()))
+            }
+            other => Err(D::Error::custom(format!("不是 mod: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy(acronym: &str) -> Option<Mods> {
+        GameMod::new(acronym).legacy()
+    }
+
+    #[test]
+    fn legacy_acronyms() {
+        assert_eq!(legacy("HD"), Some(Mods::HD));
+        assert_eq!(legacy("nc"), Some(Mods::NC));
+        assert_eq!(legacy("4K"), Some(Mods::K4));
+        assert_eq!(legacy("DS"), Some(Mods::CO));
+        assert_eq!(legacy("SV2"), Some(Mods::V2));
+        assert_eq!(legacy("CL"), Some(Mods::NONE));
+    }
+
+    #[test]
+    fn lazer_only_mods_have_no_legacy() {
+        // lazer 的 CO 是 mania 的 Cover
+        assert_eq!(legacy("CO"), None);
+        for acronym in ["NV", "CP", "K1", "K4", "K9", "NM", "NONE", "AUTO", "DA", "WG", "TC"] {
+            assert_eq!(legacy(acronym), None, "{}", acronym);
+        }
+        let speed = ModSettings { speed_change: Some(1.3), ..ModSettings::default() };
+        assert_eq!(GameMod::new("DT").with_settings(speed).legacy(), None);
+    }
+
+    #[test]
+    fn to_legacy() {
+        let mods = [GameMod::new("HD"), GameMod::new("NC"), GameMod::new("CL")];
+        assert_eq!(GameMod::to_legacy(&mods), Some(Mods::HD | Mods::NC | Mods::DT));
+        assert_eq!(GameMod::to_legacy(&[GameMod::new("HD"), GameMod::new("CO")]), None);
+        assert_eq!(GameMod::to_legacy(&[]), Some(Mods::NONE));
+    }
+
+    #[test]
+    fn from_legacy() {
+        let mods = GameMod::from_legacy(Mods::HD | Mods::NC | Mods::DT | Mods::V2 | Mods::CO);
+        let acronyms: Vec<&str> = mods.iter().map(|m| m.acronym.as_str()).collect();
+        assert_eq!(acronyms, ["HD", "NC", "DS", "SV2"]);
+        assert_eq!(GameMod::to_legacy(&mods), Some(Mods::HD | Mods::NC | Mods::DT | Mods::V2 | Mods::CO));
+    }
+
+    #[test]
+    fn deserialize() {
+        let mods: Vec<GameMod> = serde_json::from_str(r#"["HD", {"acronym":"DT","settings":{"speed_change":1.3,"foo":1}}]"#).unwrap();
+        assert_eq!(mods[0], GameMod::new("HD"));
+        assert_eq!(mods[1].settings.speed_change, Some(1.3));
+        assert_eq!(mods[1].settings.extra["foo"], 1);
+        assert_eq!(mods[1].to_string(), "DT(1.3x)");
+        assert!(serde_json::from_str::<GameMod>("1").is_err());
+    }
+}
@@ -1,58 +1,39 @@
 mod r#enum;
+mod mods;
+mod game_mod;
+pub(crate) mod de;
 
 
-use serde_json::{json, to_string, Value};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 pub use r#enum::{
-    UserType,DataType,Mode,Mods
+    UserType,DataType,Mode,RankStatus,Genre,Language
 };
+pub use mods::Mods;
+pub use game_mod::{GameMod, ModSettings};
 use crate::error::{Error, Result};
+use crate::http::api_error;
 
 pub fn data_serialize_vec<ApiData :for<'a> serde::Deserialize<'a> + serde::Serialize>(data:String) -> Result<Vec<ApiData>> {
-    let result = serde_json::from_str::<Vec<ApiData>>(data.as_str());
-    match result {
-        Ok(vec) => {
-            return if vec.is_empty(){
-                Err(Error::Null)
-            } else{
-                Ok(vec)
-            }
-        }
-        Err(err) => {
-            Err(Error::Error(err))
-        }
+    if let Some(msg) = api_error(data.as_str()) {
+        return Err(Error::Api(msg));
+    }
+    let vec = serde_json::from_str::<Vec<ApiData>>(data.as_str())?;
+    if vec.is_empty(){
+        Err(Error::Null)
+    } else{
+        Ok(vec)
     }
 }
 
 pub fn data_serialize<ApiData :for<'a> serde::Deserialize<'a> + serde::Serialize>(data:String) -> Result<ApiData> {
-    let json = serde_json::from_str::<Value>(data.as_str());
-    let json= match json {
-        Ok(data) => {
-            if !data.get("error").is_none() && data["error"].is_null() {
-                return Err(Error::Null);
-            }else {
-                Ok(data)
-            }
-        }
-        Err(err) => {
-            return Err(Error::Error(err));
-        }
-    };
-    match json {
-        Ok(data) => {
-            let result = serde_json::from_value::<ApiData>(data);
-            match result {
-                Ok(data) => {
-                    Ok(data)
-                }
-                Err(err) => {
-                    Err(Error::Error(err))
-                }
-            }
-        }
-        Err(err) => {
-            Err(err)
-        }
+    let json = serde_json::from_str::<Value>(data.as_str())?;
+    match json.get("error") {
+        Some(Value::Null) => return Err(Error::Null),
+        Some(Value::String(msg)) => return Err(Error::Api(msg.clone())),
+        _ => {}
     }
+    Ok(serde_json::from_value::<ApiData>(json)?)
 }
 
 pub fn assembly_user_type(user:UserType<'_>, vec: &mut Vec<(&str, String)>){
@@ -75,11 +56,6 @@ pub fn assembly_data<'a>(data:&[(&'a str, DataType)], vec: &mut Vec<(&'a str, St
                    vec.push((k,i.to_string()));
                }
            }
-           DataType::Int32(i) => {
-               if let Some(i) = i {
-                   vec.push((k,i.to_string()));
-               }
-           }
            DataType::Int16(i) => {
                if let Some(i) = i {
                    vec.push((k,i.to_string()));
@@ -95,22 +71,27 @@ pub fn assembly_data<'a>(data:&[(&'a str, DataType)], vec: &mut Vec<(&'a str, St
                    vec.push((k,str.to_string()));
                }
            }
-           DataType::Mods(v) => {
-               if let Some(m) = v {
-                   let vec = Mods::get_mods(m);
-                   // vec.push((k,format!("{:?}",v)));
+           DataType::Mods(m,is_str) => {
+               if let Some(m) = m {
+                   if *is_str {
+                       // NONE 时发送 NM, 只返回没有 mod 的成绩
+                       if m.is_empty() {
+                           vec.push((k,"NM".to_string()));
+                       }
+                       for acronym in m.acronyms() {
+                           vec.push((k,acronym.to_string()));
+                       }
+                   }else {
+                       vec.push((k,m.bits().to_string()));
+                   }
                }
            }
            DataType::Mode(m,is_str) => {
-               if *is_str {
-                   if let Some(m) = m {
-                       let (str,_) = Mode::get_mode(m);
-                       vec.push((k,format!("{:?}",str)));
-                   }
-               }else {
-                   if let Some(m) = m {
-                       let (_,i) = Mode::get_mode(m);
-                       vec.push((k,format!("{:?}",i)));
+               if let Some(m) = m {
+                   if *is_str {
+                       vec.push((k,m.name().to_string()));
+                   }else {
+                       vec.push((k,m.id().to_string()));
                    }
                }
 
@@ -119,85 +100,208 @@ pub fn assembly_data<'a>(data:&[(&'a str, DataType)], vec: &mut Vec<(&'a str, St
    }
 }
 
-impl Mods {
-    pub fn get_mods<'a>(mods:&Vec<Mods>) -> Vec<(i32,&'a str)> {
-        let mut vec = vec![];
-        for m in mods {
-            match m {
-                Mods::NONE => vec.push((0, "NONE")),
-                Mods::NF => vec.push((1, "NF")),
-                Mods::EZ => vec.push((2, "EZ")),
-                Mods::NV => vec.push((4, "NV")),
-                Mods::MR => vec.push((0, "MR")),
-                Mods::HD => vec.push((8, "HD")),
-                Mods::HR => vec.push((16, "HR")),
-                Mods::SD => vec.push((32, "SD")),
-                Mods::DT => vec.push((64, "DT")),
-                Mods::RX => vec.push((128, "RX")),
-                Mods::HT => vec.push((256, "HT")),
-                Mods::NC => vec.push((512, "NC")),
-                Mods::FL => vec.push((1024, "FL")),
-                Mods::AUTO => vec.push((2048, "Auto")),
-                Mods::SO => vec.push((4096, "SO")),
-                Mods::AP => vec.push((8192, "AP")),
-                Mods::PF => vec.push((16384, "PF")),
-                Mods::Key1 => vec.push((67108864,"1K")),
-                Mods::Key2 => vec.push((268435456,"2K")),
-                Mods::Key3 => vec.push((134217728,"3K")),
-                Mods::Key4 => vec.push((32768,"4K")),
-                Mods::Key5 => vec.push((65536,"5K")),
-                Mods::Key6 => vec.push((131072,"6K")),
-                Mods::Key7 => vec.push((262144,"7K")),
-                Mods::Key8 => vec.push((524288,"8K")),
-                Mods::Key9 => vec.push((2,"9K")),
-                Mods::Key10 => vec.push((2,"10K")),
-                Mods::FadeIn => vec.push((1048576,"FadeIn")),
-                Mods::Random => vec.push((2097152,"Random")),
-                Mods::Cinema => vec.push((4194304,"Cinema")),
-                Mods::Coop => vec.push((33554432, "Coop")),
-                Mods::V2 => vec.push((536870912, "V2")),
-                Mods::LM => vec.push((1073741824, "LM")),
-                Mods::KeyMod => {
-                    // vec.push((2,"KeyMod"));
-                },
-                Mods::FreeModAllowed => {
-                    // vec.push((2,"FreeModAllowed"))
+impl Mode {
+    /// (名称, 数字), 例: `("osu", 0)`
+    pub fn get_mode<'a>(mode:&Mode) -> (&'a str,i8) {
+        (mode.name(), mode.id())
+    }
+}
+
+/// 为 `Mode` / `RankStatus` / `Genre` / `Language` 生成 数字/字符串 的相互转换
+///
+/// 序列化为数字 (同 v1), 反序列化兼容 数字 / 数字字符串 (v1) / 名称 (v2 的 `mode`, `status`)
+///
+/// 第一个名称用于 [`Display`](std::fmt::Display), 后面的是解析时兼容的别名
+macro_rules! id_enum {
+    ($ty:ident, $what:literal, { $($variant:ident => $name:literal $(| $alias:literal)*),* $(,)? }) => {
+        impl $ty {
+            /// 数字 id
+            pub fn id(&self) -> i8 {
+                *self as i8
+            }
+            /// 名称, 同 v2 返回的字符串
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($ty::$variant => $name,)*
                 }
             }
         }
-        vec
-    }
-    pub fn str_to_mods(str:Vec<&str>) -> Vec<Mods>{
-        let mut vec = vec![];
-        for s in str {
-            match  s{
-                "NONE" => vec.push(Mods::NONE),
-                "NF" => vec.push(Mods::NF),
-                "EZ" => vec.push(Mods::EZ),
-                "NV" => vec.push(Mods::NV),
-                _ => {}
+
+        impl TryFrom<i64> for $ty {
+            type Error = Error;
+
+            fn try_from(id: i64) -> Result<Self> {
+                $(if id == $ty::$variant as i64 {
+                    return Ok($ty::$variant);
+                })*
+                Err(Error::InvalidInput(format!(concat!("未知的", $what, ": {}"), id)))
             }
         }
-        vec
-    }
-}
 
+        /// 兼容数字和名称, 名称不区分大小写, 空格和 `_` 视为 `-`
+        impl std::str::FromStr for $ty {
+            type Err = Error;
 
-impl Mode {
-    pub fn get_mode<'a>(mode:&Mode) -> (&'a str,i8) {
-        match mode {
-            Mode::Fruits => {
-                ("fruits",2)
+            fn from_str(s: &str) -> Result<Self> {
+                let s = s.trim();
+                if let Ok(id) = s.parse::<i64>() {
+                    return $ty::try_from(id);
+                }
+                let name = s.to_ascii_lowercase().replace([' ', '_'], "-");
+                match name.as_str() {
+                    $($name $(| $alias)* => Ok($ty::$variant),)*
+                    _ => Err(Error::InvalidInput(format!(concat!("未知的", $what, ": {}"), s))),
+                }
             }
-            Mode::Mania => {
-                ("mania",3)
+        }
+
+        impl std::fmt::Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
             }
-            Mode::Osu => {
-                ("osu",0)
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_i8(self.id())
             }
-            Mode::Taiko => {
-                ("taiko",1)
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                de::from_str::<D, $ty>(deserializer)
             }
         }
+    };
+}
+
+id_enum!(Mode, "mode", {
+    Osu => "osu" | "std" | "standard" | "o" | "osu!" | "osu!std" | "osu!standard",
+    Taiko => "taiko" | "t" | "o!t" | "osu!taiko",
+    Fruits => "fruits" | "fruit" | "catch" | "ctb" | "c" | "o!c" | "osu!catch" | "catch-the-beat",
+    Mania => "mania" | "m" | "o!m" | "osu!mania",
+});
+
+id_enum!(RankStatus, "谱面状态", {
+    Graveyard => "graveyard",
+    Wip => "wip",
+    Pending => "pending",
+    Ranked => "ranked",
+    Approved => "approved",
+    Qualified => "qualified",
+    Loved => "loved",
+});
+
+id_enum!(Genre, "曲风", {
+    Any => "any",
+    Unspecified => "unspecified",
+    VideoGame => "video-game",
+    Anime => "anime",
+    Rock => "rock",
+    Pop => "pop",
+    Other => "other",
+    Novelty => "novelty",
+    HipHop => "hip-hop",
+    Electronic => "electronic",
+    Metal => "metal",
+    Classical => "classical",
+    Folk => "folk",
+    Jazz => "jazz",
+});
+
+id_enum!(Language, "语言", {
+    Any => "any",
+    Other => "other",
+    English => "english",
+    Japanese => "japanese",
+    Chinese => "chinese",
+    Instrumental => "instrumental",
+    Korean => "korean",
+    French => "french",
+    German => "german",
+    Swedish => "swedish",
+    Spanish => "spanish",
+    Italian => "italian",
+    Russian => "russian",
+    Polish => "polish",
+    Unspecified => "unspecified",
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_names_and_aliases() {
+        for (s, mode) in [
+            ("osu", Mode::Osu), ("OSU", Mode::Osu), ("std", Mode::Osu), ("osu!standard", Mode::Osu),
+            ("taiko", Mode::Taiko), ("o!t", Mode::Taiko),
+            ("fruits", Mode::Fruits), ("ctb", Mode::Fruits), ("Catch The Beat", Mode::Fruits), ("catch_the_beat", Mode::Fruits),
+            ("mania", Mode::Mania), ("osu!mania", Mode::Mania), (" m ", Mode::Mania),
+        ] {
+            assert_eq!(s.parse::<Mode>().unwrap(), mode, "{}", s);
+        }
+        assert!("".parse::<Mode>().is_err());
+        assert!("osu!droid".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn mode_ids() {
+        for mode in [Mode::Osu, Mode::Taiko, Mode::Fruits, Mode::Mania] {
+            assert_eq!(mode.id().to_string().parse::<Mode>().unwrap(), mode);
+            assert_eq!(mode.name().parse::<Mode>().unwrap(), mode);
+            assert_eq!(Mode::try_from(mode.id() as i64).unwrap(), mode);
+        }
+        assert_eq!(Mode::Fruits.id(), 2);
+        assert_eq!(Mode::Fruits.to_string(), "fruits");
+        for id in ["4", "-1", "300"] {
+            assert!(matches!(id.parse::<Mode>(), Err(Error::InvalidInput(_))), "{}", id);
+        }
+        assert!(Mode::try_from(4).is_err());
+    }
+
+    #[test]
+    fn mode_serde() {
+        assert_eq!(serde_json::to_string(&Mode::Mania).unwrap(), "3");
+        for json in ["3", r#""3""#, r#""mania""#] {
+            assert_eq!(serde_json::from_str::<Mode>(json).unwrap(), Mode::Mania, "{}", json);
+        }
+        for json in ["4", r#""4""#, r#""osu!droid""#, "null"] {
+            assert!(serde_json::from_str::<Mode>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn rank_status() {
+        assert_eq!("-2".parse::<RankStatus>().unwrap(), RankStatus::Graveyard);
+        assert_eq!("WIP".parse::<RankStatus>().unwrap(), RankStatus::Wip);
+        assert_eq!(serde_json::from_str::<RankStatus>(r#""loved""#).unwrap(), RankStatus::Loved);
+        assert_eq!(serde_json::from_str::<RankStatus>(r#""4""#).unwrap(), RankStatus::Loved);
+        assert_eq!(serde_json::to_string(&RankStatus::Graveyard).unwrap(), "-2");
+        assert_eq!(RankStatus::Qualified.to_string(), "qualified");
+        assert!("5".parse::<RankStatus>().is_err());
+        assert!("-3".parse::<RankStatus>().is_err());
+        assert!("ranked!".parse::<RankStatus>().is_err());
+    }
+
+    #[test]
+    fn genre() {
+        assert_eq!("video game".parse::<Genre>().unwrap(), Genre::VideoGame);
+        assert_eq!("Hip_Hop".parse::<Genre>().unwrap(), Genre::HipHop);
+        assert_eq!("9".parse::<Genre>().unwrap(), Genre::HipHop);
+        assert_eq!(Genre::VideoGame.to_string(), "video-game");
+        assert_eq!(serde_json::to_string(&Genre::Jazz).unwrap(), "14");
+        // 没有 8
+        assert!("8".parse::<Genre>().is_err());
+        assert!("15".parse::<Genre>().is_err());
+    }
+
+    #[test]
+    fn language() {
+        assert_eq!("Japanese".parse::<Language>().unwrap(), Language::Japanese);
+        assert_eq!(serde_json::from_str::<Language>(r#""14""#).unwrap(), Language::Unspecified);
+        assert_eq!(Language::Instrumental.id(), 5);
+        assert!("15".parse::<Language>().is_err());
+        assert!("klingon".parse::<Language>().is_err());
     }
-}
\ No newline at end of file
+}
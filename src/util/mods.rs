@@ -0,0 +1,614 @@
+//! osu Mods
+//!
+//! 按 v1 (旧版客户端) 的位定义, `enabled_mods` 为这些位的组合, 例: `"88"` = HD + HR + DT
+//!
+//! 引用: https://github.com/ppy/osu-api/wiki#mods
+
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign};
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as _;
+use serde_json::Value;
+use crate::error::{Error, Result};
+use super::Mode;
+
+/// Mods 的集合, 可以用 `|` `&` `-` 组合
+/// ```
+/// use osu_api_rs::Mods;
+///
+/// let mods = Mods::HD | Mods::DT;
+/// assert_eq!(mods.bits(), 72);
+/// assert_eq!(mods.to_string(), "HDDT");
+/// assert_eq!("HDDT".parse::<Mods>().unwrap(), mods);
+/// assert_eq!("72".parse::<Mods>().unwrap(), mods);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Mods(u32);
+
+/// (mod, 缩写), 按位从低到高的顺序
+const ACRONYMS: &[(Mods, &str)] = &[
+    (Mods::NF, "NF"),
+    (Mods::EZ, "EZ"),
+    (Mods::TD, "TD"),
+    (Mods::HD, "HD"),
+    (Mods::HR, "HR"),
+    (Mods::SD, "SD"),
+    (Mods::DT, "DT"),
+    (Mods::RX, "RX"),
+    (Mods::HT, "HT"),
+    (Mods::NC, "NC"),
+    (Mods::FL, "FL"),
+    (Mods::AUTO, "AT"),
+    (Mods::SO, "SO"),
+    (Mods::AP, "AP"),
+    (Mods::PF, "PF"),
+    (Mods::K4, "4K"),
+    (Mods::K5, "5K"),
+    (Mods::K6, "6K"),
+    (Mods::K7, "7K"),
+    (Mods::K8, "8K"),
+    (Mods::FI, "FI"),
+    (Mods::RD, "RD"),
+    (Mods::CN, "CN"),
+    (Mods::TP, "TP"),
+    (Mods::K9, "9K"),
+    (Mods::CO, "DS"),
+    (Mods::K1, "1K"),
+    (Mods::K3, "3K"),
+    (Mods::K2, "2K"),
+    (Mods::V2, "V2"),
+    (Mods::MR, "MR"),
+];
+
+/// 其他写法的缩写
+const ALIASES: &[(&str, Mods)] = &[
+    ("NM", Mods::NONE),
+    ("NONE", Mods::NONE),
+    ("NV", Mods::TD),
+    ("AUTO", Mods::AUTO),
+    ("CP", Mods::CN),
+    ("CO", Mods::CO),
+    ("SV2", Mods::V2),
+    ("K1", Mods::K1),
+    ("K2", Mods::K2),
+    ("K3", Mods::K3),
+    ("K4", Mods::K4),
+    ("K5", Mods::K5),
+    ("K6", Mods::K6),
+    ("K7", Mods::K7),
+    ("K8", Mods::K8),
+    ("K9", Mods::K9),
+];
+
+impl Mods {
+    pub const NONE: Mods = Mods(0);
+    /// NoFail
+    pub const NF: Mods = Mods(1);
+    /// Easy
+    pub const EZ: Mods = Mods(1 << 1);
+    /// TouchDevice
+    pub const TD: Mods = Mods(1 << 2);
+    /// TouchDevice 的旧名 (NoVideo)
+    pub const NV: Mods = Mods::TD;
+    /// Hidden
+    pub const HD: Mods = Mods(1 << 3);
+    /// HardRock
+    pub const HR: Mods = Mods(1 << 4);
+    /// SuddenDeath
+    pub const SD: Mods = Mods(1 << 5);
+    /// DoubleTime
+    pub const DT: Mods = Mods(1 << 6);
+    /// Relax
+    pub const RX: Mods = Mods(1 << 7);
+    /// HalfTime
+    pub const HT: Mods = Mods(1 << 8);
+    /// Nightcore, 旧版客户端中总是和 DT 一起出现
+    pub const NC: Mods = Mods(1 << 9);
+    /// Flashlight
+    pub const FL: Mods = Mods(1 << 10);
+    /// Autoplay
+    pub const AUTO: Mods = Mods(1 << 11);
+    /// SpunOut
+    pub const SO: Mods = Mods(1 << 12);
+    /// Autopilot
+    pub const AP: Mods = Mods(1 << 13);
+    /// Perfect, 旧版客户端中总是和 SD 一起出现
+    pub const PF: Mods = Mods(1 << 14);
+    pub const K4: Mods = Mods(1 << 15);
+    pub const K5: Mods = Mods(1 << 16);
+    pub const K6: Mods = Mods(1 << 17);
+    pub const K7: Mods = Mods(1 << 18);
+    pub const K8: Mods = Mods(1 << 19);
+    /// FadeIn
+    pub const FI: Mods = Mods(1 << 20);
+    /// Random
+    pub const RD: Mods = Mods(1 << 21);
+    /// Cinema
+    pub const CN: Mods = Mods(1 << 22);
+    /// TargetPractice
+    pub const TP: Mods = Mods(1 << 23);
+    pub const K9: Mods = Mods(1 << 24);
+    /// KeyCoop (双人)
+    pub const CO: Mods = Mods(1 << 25);
+    pub const K1: Mods = Mods(1 << 26);
+    pub const K3: Mods = Mods(1 << 27);
+    pub const K2: Mods = Mods(1 << 28);
+    /// ScoreV2
+    pub const V2: Mods = Mods(1 << 29);
+    /// Mirror
+    pub const MR: Mods = Mods(1 << 30);
+
+    /// 所有的 key mod
+    pub const KEY_MOD: Mods = Mods(Mods::K1.0 | Mods::K2.0 | Mods::K3.0 | Mods::K4.0 | Mods::K5.0
+        | Mods::K6.0 | Mods::K7.0 | Mods::K8.0 | Mods::K9.0 | Mods::CO.0);
+    /// 多人游戏中 free mod 允许选择的 mod
+    pub const FREE_MOD_ALLOWED: Mods = Mods(Mods::NF.0 | Mods::EZ.0 | Mods::HD.0 | Mods::HR.0 | Mods::SD.0
+        | Mods::FL.0 | Mods::FI.0 | Mods::RX.0 | Mods::AP.0 | Mods::SO.0 | Mods::KEY_MOD.0);
+    /// 会提高分数的 mod
+    pub const SCORE_INCREASE_MODS: Mods = Mods(Mods::HD.0 | Mods::HR.0 | Mods::DT.0 | Mods::FL.0 | Mods::FI.0);
+    /// 所有已知的位
+    pub const ALL: Mods = Mods((1 << 31) - 1);
+
+    /// 位的组合
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    /// 有未知的位时返回 None
+    pub const fn from_bits(bits: u32) -> Option<Mods> {
+        if bits & !Mods::ALL.0 == 0 {
+            Some(Mods(bits))
+        } else {
+            None
+        }
+    }
+    /// 忽略未知的位
+    pub const fn from_bits_truncate(bits: u32) -> Mods {
+        Mods(bits & Mods::ALL.0)
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    /// 是否包括 `other` 中的所有 mod
+    pub const fn contains(&self, other: Mods) -> bool {
+        self.0 & other.0 == other.0
+    }
+    /// 是否包括 `other` 中的任意一个 mod
+    pub const fn intersects(&self, other: Mods) -> bool {
+        self.0 & other.0 != 0
+    }
+    pub fn insert(&mut self, other: Mods) {
+        self.0 |= other.0;
+    }
+    pub fn remove(&mut self, other: Mods) {
+        self.0 &= !other.0;
+    }
+    /// 有 `other` 时移除, 没有时加上
+    pub fn toggle(&mut self, other: Mods) {
+        self.0 ^= other.0;
+    }
+    /// mod 的数量
+    pub const fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+    /// 逐个返回单个的 mod, 顺序同 [`Display`](fmt::Display)
+    pub fn iter(&self) -> impl Iterator<Item = Mods> + '_ {
+        ACRONYMS.iter().map(|(m, _)| *m).filter(move |m| self.contains(*m))
+    }
+    /// 单个 mod 的缩写, 不是单个 mod 时返回 None
+    pub fn acronym(&self) -> Option<&'static str> {
+        ACRONYMS.iter().find(|(m, _)| m == self).map(|(_, s)| *s)
+    }
+    /// 逐个返回 mod 的缩写, 例: `["HD", "DT"]`
+    ///
+    /// 同 [`Display`](fmt::Display), NC 和 PF 不会再带上 DT 和 SD
+    pub fn acronyms(&self) -> Vec<&'static str> {
+        let mut mods = *self;
+        if mods.contains(Mods::NC) {
+            mods.remove(Mods::DT);
+        }
+        if mods.contains(Mods::PF) {
+            mods.remove(Mods::SD);
+        }
+        mods.iter().filter_map(|m| m.acronym()).collect()
+    }
+    /// 单个缩写 -> mod, 不区分大小写, 也兼容其他写法 (例: `NV`, `K4`, `SV2`)
+    pub fn from_acronym(acronym: &str) -> Option<Mods> {
+        let acronym = acronym.to_ascii_uppercase();
+        Mods::from_canonical_acronym(&acronym)
+            .or_else(|| ALIASES.iter().find(|(s, _)| *s == acronym).map(|(_, m)| *m))
+    }
+    /// 只匹配标准的缩写 (区分大小写), 不兼容其他写法
+    pub(crate) fn from_canonical_acronym(acronym: &str) -> Option<Mods> {
+        ACRONYMS.iter().find(|(_, s)| *s == acronym).map(|(m, _)| *m)
+    }
+}
+
+/// 互斥的 mod, 每组中最多只能有一个 (组中的元素可以是多个 mod, 例: DT 和 NC 算一个)
+const EXCLUSIVE: &[&[Mods]] = &[
+    &[Mods::EZ, Mods::HR],
+    &[Mods(Mods::DT.0 | Mods::NC.0), Mods::HT],
+    &[Mods::NF, Mods(Mods::SD.0 | Mods::PF.0)],
+    &[Mods::HD, Mods::FI],
+    &[Mods::RX, Mods::AP, Mods::AUTO, Mods::CN],
+    &[Mods::SO, Mods::AP],
+    &[Mods::K1, Mods::K2, Mods::K3, Mods::K4, Mods::K5, Mods::K6, Mods::K7, Mods::K8, Mods::K9],
+];
+
+/// (被包括的 mod, 包括它的 mod), 旧版客户端中 NC 总是带着 DT, PF 总是带着 SD
+const IMPLIED: &[(Mods, Mods)] = &[
+    (Mods::NC, Mods::DT),
+    (Mods::PF, Mods::SD),
+];
+
+/// 只能在 osu! 中使用的 mod
+const OSU_ONLY: Mods = Mods(Mods::AP.0 | Mods::SO.0 | Mods::TP.0 | Mods::TD.0);
+/// 只能在 mania 中使用的 mod
+const MANIA_ONLY: Mods = Mods(Mods::KEY_MOD.0 | Mods::FI.0 | Mods::RD.0 | Mods::MR.0);
+
+impl Mods {
+    /// 会改变难度 (星数 / pp) 的 mod
+    pub const DIFFICULTY_MODS: Mods = Mods(Mods::EZ.0 | Mods::HR.0 | Mods::DT.0 | Mods::NC.0 | Mods::HT.0
+        | Mods::HD.0 | Mods::FL.0 | Mods::RX.0 | Mods::AP.0 | Mods::SO.0 | Mods::TD.0 | Mods::KEY_MOD.0);
+
+    /// 检查 mod 能否同时使用, 设置了 `mode` 时还会检查该模式能否使用
+    /// ```
+    /// use osu_api_rs::{Mode, Mods};
+    ///
+    /// assert!((Mods::HD | Mods::DT).validate(Some(Mode::Osu)).is_ok());
+    /// assert!((Mods::HR | Mods::EZ).validate(None).is_err());
+    /// assert!(Mods::K4.validate(Some(Mode::Osu)).is_err());
+    /// ```
+    pub fn validate(&self, mode: Option<Mode>) -> Result<()> {
+        for group in EXCLUSIVE {
+            let used: Vec<Mods> = group.iter().map(|m| *self & *m).filter(|m| !m.is_empty()).collect();
+            if used.len() > 1 {
+                let used: Vec<String> = used.iter().map(Mods::to_string).collect();
+                return Err(Error::InvalidInput(format!("mod 冲突: {} 不能同时使用", used.join(" 和 "))));
+            }
+        }
+        if let Some(mode) = mode {
+            let (name, _) = Mode::get_mode(&mode);
+            let invalid = match mode {
+                Mode::Osu => *self & MANIA_ONLY,
+                Mode::Mania => *self & (OSU_ONLY | Mods::RX),
+                Mode::Taiko | Mode::Fruits => *self & (OSU_ONLY | MANIA_ONLY),
+            };
+            if !invalid.is_empty() {
+                return Err(Error::InvalidInput(format!("{} 不能在 {} 中使用", invalid.acronyms().join(", "), name)));
+            }
+        }
+        Ok(())
+    }
+    pub fn is_valid(&self, mode: Option<Mode>) -> bool {
+        self.validate(mode).is_ok()
+    }
+    /// 补上被包括的 mod (NC -> NC + DT, PF -> PF + SD), 同旧版客户端保存成绩时的格式
+    pub fn with_implied(&self) -> Mods {
+        let mut mods = *self;
+        for (m, implied) in IMPLIED {
+            if mods.contains(*m) {
+                mods.insert(*implied);
+            }
+        }
+        mods
+    }
+    /// 检查后转换为 osu! 排行榜 / pp 计算使用的格式
+    ///
+    /// 补上被包括的 mod (见 [`with_implied`](Mods::with_implied)), 并去掉该模式中无效的 mod
+    /// (例: 非 mania 模式的 key mod 是不允许的, 会返回错误; 而 TD 在 osu! 之外没有效果, 会被去掉)
+    pub fn canonicalize(&self, mode: Option<Mode>) -> Result<Mods> {
+        let mut mods = self.with_implied();
+        // TD 只是标记, 在 osu! 之外的模式里客户端会直接忽略
+        if !matches!(mode, None | Some(Mode::Osu)) {
+            mods.remove(Mods::TD);
+        }
+        mods.validate(mode)?;
+        Ok(mods)
+    }
+    /// 只保留会改变难度的 mod, 用于计算星数 / pp
+    pub fn difficulty_mods(&self) -> Mods {
+        *self & Mods::DIFFICULTY_MODS
+    }
+}
+
+/// 缩写连在一起, 例: `HDDT`, 没有 mod 时为 `NM`
+impl fmt::Display for Mods {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("NM");
+        }
+        for acronym in self.acronyms() {
+            f.write_str(acronym)?;
+        }
+        Ok(())
+    }
+}
+
+/// 兼容:
+/// * 数字, 例: `"76"` (v1 的 `enabled_mods`)
+/// * 连在一起的缩写, 例: `"HDDTHR"` / `"+HDDT"`
+/// * 分隔的缩写, 例: `"HD,DT"` / `"HD DT"`
+impl FromStr for Mods {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Ok(bits) = s.parse::<u32>() {
+            return Mods::from_bits(bits).ok_or_else(|| Error::InvalidInput(format!("未知的 mods: {}", s)));
+        }
+        let mut mods = Mods::NONE;
+        for part in s.trim_start_matches('+').split(|c: char| c == ',' || c == '|' || c.is_whitespace()) {
+            let mut rest = part;
+            while !rest.is_empty() {
+                // 先匹配 4/3 个字符的 (NONE, AUTO, SV2), 再匹配 2 个字符的
+                let len = [4, 3, 2].into_iter()
+                    .find(|len| rest.get(..*len).and_then(Mods::from_acronym).is_some())
+                    .ok_or_else(|| Error::InvalidInput(format!("未知的 mod: {}", rest)))?;
+                mods.insert(Mods::from_acronym(&rest[..len]).unwrap_or_default());
+                rest = &rest[len..];
+            }
+        }
+        Ok(mods)
+    }
+}
+
+impl TryFrom<u32> for Mods {
+    type Error = Error;
+
+    fn try_from(bits: u32) -> Result<Self> {
+        Mods::from_bits(bits).ok_or_else(|| Error::InvalidInput(format!("未知的 mods: {}", bits)))
+    }
+}
+
+impl From<Mods> for u32 {
+    fn from(mods: Mods) -> u32 {
+        mods.0
+    }
+}
+
+impl FromIterator<Mods> for Mods {
+    fn from_iter<I: IntoIterator<Item = Mods>>(iter: I) -> Self {
+        iter.into_iter().fold(Mods::NONE, |a, b| a | b)
+    }
+}
+
+impl BitOr for Mods {
+    type Output = Mods;
+    fn bitor(self, rhs: Mods) -> Mods {
+        Mods(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Mods {
+    fn bitor_assign(&mut self, rhs: Mods) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Mods {
+    type Output = Mods;
+    fn bitand(self, rhs: Mods) -> Mods {
+        Mods(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Mods {
+    fn bitand_assign(&mut self, rhs: Mods) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitXor for Mods {
+    type Output = Mods;
+    fn bitxor(self, rhs: Mods) -> Mods {
+        Mods(self.0 ^ rhs.0)
+    }
+}
+
+impl BitXorAssign for Mods {
+    fn bitxor_assign(&mut self, rhs: Mods) {
+        self.0 ^= rhs.0;
+    }
+}
+
+/// 差集
+impl Sub for Mods {
+    type Output = Mods;
+    fn sub(self, rhs: Mods) -> Mods {
+        Mods(self.0 & !rhs.0)
+    }
+}
+
+impl SubAssign for Mods {
+    fn sub_assign(&mut self, rhs: Mods) {
+        self.0 &= !rhs.0;
+    }
+}
+
+/// 补集 (只包括已知的位)
+impl Not for Mods {
+    type Output = Mods;
+    fn not(self) -> Mods {
+        Mods(!self.0 & Mods::ALL.0)
+    }
+}
+
+/// 序列化为数字, 同 v1
+impl Serialize for Mods {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+/// 兼容 数字 / 字符串 (见 [`FromStr`]) / 缩写数组 (v2 的 `["HD", "DT"]`), null 为 NONE
+impl<'de> Deserialize<'de> for Mods {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Null => Ok(Mods::NONE),
+            Value::Number(n) => n.as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .and_then(Mods::from_bits)
+                .ok_or_else(|| D::Error::custom(format!("未知的 mods: {}", n))),
+            Value::String(s) if s.is_empty() => Ok(Mods::NONE),
+            Value::String(s) => s.parse().map_err(D::Error::custom),
+            Value::Array(vec) => vec.into_iter().map(|v| match v {
+                Value::String(s) => s.parse::<Mods>().map_err(D::Error::custom),
+                other => Err(D::Error::custom(format!("不是 mod: {}", other))),
+            }).collect(),
+            other => Err(D::Error::custom(format!("不是 mods: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Mods {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_concatenated() {
+        assert_eq!(parse("HDDTHR"), Mods::HD | Mods::DT | Mods::HR);
+        assert_eq!(parse("hddthr"), Mods::HD | Mods::DT | Mods::HR);
+        assert_eq!(parse("+HDDT"), Mods::HD | Mods::DT);
+        assert_eq!(parse("4KMR"), Mods::K4 | Mods::MR);
+    }
+
+    #[test]
+    fn parse_separated() {
+        assert_eq!(parse("+HD,DT"), Mods::HD | Mods::DT);
+        assert_eq!(parse("HD DT"), Mods::HD | Mods::DT);
+        assert_eq!(parse("HD|NC, FL"), Mods::HD | Mods::NC | Mods::FL);
+    }
+
+    #[test]
+    fn parse_long_acronyms_first() {
+        assert_eq!(parse("SV2HD"), Mods::V2 | Mods::HD);
+        assert_eq!(parse("HDSV2"), Mods::HD | Mods::V2);
+        assert_eq!(parse("AUTO"), Mods::AUTO);
+        assert_eq!(parse("NONE"), Mods::NONE);
+        assert_eq!(parse("NM"), Mods::NONE);
+    }
+
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(parse("76"), Mods::HD | Mods::DT | Mods::TD);
+        assert_eq!(parse("0"), Mods::NONE);
+        assert_eq!(parse(" 88 "), Mods::HD | Mods::HR | Mods::DT);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("HDXX".parse::<Mods>().is_err());
+        assert!("H".parse::<Mods>().is_err());
+        assert!((1u32 << 31).to_string().parse::<Mods>().is_err());
+    }
+
+    #[test]
+    fn unknown_bits() {
+        assert_eq!(Mods::from_bits(1 << 31), None);
+        assert_eq!(Mods::from_bits(72), Some(Mods::HD | Mods::DT));
+        assert_eq!(Mods::from_bits_truncate((1 << 31) | 8), Mods::HD);
+        assert!(Mods::try_from(u32::MAX).is_err());
+        assert_eq!(!Mods::NONE, Mods::ALL);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Mods::NONE.to_string(), "NM");
+        assert_eq!((Mods::DT | Mods::HD | Mods::HR).to_string(), "HDHRDT");
+        assert_eq!((Mods::NC | Mods::DT | Mods::HD).to_string(), "HDNC");
+        assert_eq!(Mods::NC.to_string(), "NC");
+        assert_eq!((Mods::PF | Mods::SD).to_string(), "PF");
+        assert_eq!((Mods::K4 | Mods::CO).to_string(), "4KDS");
+        assert_eq!((Mods::NC | Mods::DT).acronyms(), vec!["NC"]);
+        // Display 的结果可以再解析回来 (NC / PF 除外, 它们不会带上 DT / SD)
+        let mods = Mods::HD | Mods::HR | Mods::V2 | Mods::MR;
+        assert_eq!(parse(&mods.to_string()), mods);
+    }
+
+    #[test]
+    fn deserialize() {
+        let from = |json: &str| serde_json::from_str::<Mods>(json);
+        assert_eq!(from("72").unwrap(), Mods::HD | Mods::DT);
+        assert_eq!(from(r#""72""#).unwrap(), Mods::HD | Mods::DT);
+        assert_eq!(from(r#""HDDT""#).unwrap(), Mods::HD | Mods::DT);
+        assert_eq!(from(r#"["HD", "DT"]"#).unwrap(), Mods::HD | Mods::DT);
+        assert_eq!(from(r#"[]"#).unwrap(), Mods::NONE);
+        assert_eq!(from("null").unwrap(), Mods::NONE);
+        assert_eq!(from(r#""""#).unwrap(), Mods::NONE);
+        assert!(from("2147483648").is_err());
+        assert!(from("-1").is_err());
+        assert!(from(r#"["HD", 1]"#).is_err());
+        assert!(from("{}").is_err());
+    }
+
+    #[test]
+    fn serialize() {
+        let mods = Mods::HD | Mods::DT;
+        assert_eq!(serde_json::to_string(&mods).unwrap(), "72");
+        assert_eq!(serde_json::from_str::<Mods>(&serde_json::to_string(&mods).unwrap()).unwrap(), mods);
+    }
+
+    #[test]
+    fn exclusive_mods() {
+        assert!((Mods::EZ | Mods::HR).validate(None).is_err());
+        assert!((Mods::DT | Mods::HT).validate(None).is_err());
+        assert!((Mods::NC | Mods::HT).validate(None).is_err());
+        assert!((Mods::NC | Mods::DT | Mods::HT).validate(None).is_err());
+        // NC + DT 算一个
+        assert!((Mods::NC | Mods::DT | Mods::HD).validate(None).is_ok());
+        assert!((Mods::PF | Mods::SD).validate(None).is_ok());
+        assert!((Mods::NF | Mods::PF).validate(None).is_err());
+        assert!((Mods::HD | Mods::FI).validate(None).is_err());
+        assert!((Mods::RX | Mods::AP).validate(None).is_err());
+        assert!((Mods::K4 | Mods::K7).validate(None).is_err());
+        assert!((Mods::K4 | Mods::CO).validate(Some(Mode::Mania)).is_ok());
+    }
+
+    #[test]
+    fn mode_only_mods() {
+        // key mod 只能在 mania 中使用
+        assert!(Mods::K4.validate(Some(Mode::Osu)).is_err());
+        assert!(Mods::K4.validate(Some(Mode::Taiko)).is_err());
+        assert!(Mods::K4.validate(Some(Mode::Fruits)).is_err());
+        assert!((Mods::K7 | Mods::MR).validate(Some(Mode::Mania)).is_ok());
+        assert!(Mods::K4.validate(None).is_ok());
+        // mania 中没有 RX / AP / SO
+        assert!(Mods::RX.validate(Some(Mode::Mania)).is_err());
+        assert!(Mods::AP.validate(Some(Mode::Mania)).is_err());
+        assert!(Mods::RX.validate(Some(Mode::Taiko)).is_ok());
+        assert!((Mods::AP | Mods::SO).validate(Some(Mode::Osu)).is_err());
+        assert!(Mods::SO.validate(Some(Mode::Fruits)).is_err());
+        assert!(!Mods::FI.is_valid(Some(Mode::Osu)));
+        assert!((Mods::HD | Mods::HR | Mods::DT).is_valid(Some(Mode::Taiko)));
+    }
+
+    #[test]
+    fn implied_mods() {
+        assert_eq!(Mods::NC.with_implied(), Mods::NC | Mods::DT);
+        assert_eq!(Mods::PF.with_implied(), Mods::PF | Mods::SD);
+        assert_eq!((Mods::HD | Mods::HR).with_implied(), Mods::HD | Mods::HR);
+    }
+
+    #[test]
+    fn canonicalize() {
+        assert_eq!((Mods::NC | Mods::HD).canonicalize(Some(Mode::Osu)).unwrap(), Mods::NC | Mods::DT | Mods::HD);
+        // TD 只在 osu! 中保留
+        assert_eq!((Mods::TD | Mods::HD).canonicalize(Some(Mode::Osu)).unwrap(), Mods::TD | Mods::HD);
+        assert_eq!((Mods::TD | Mods::HD).canonicalize(None).unwrap(), Mods::TD | Mods::HD);
+        assert_eq!((Mods::TD | Mods::HD).canonicalize(Some(Mode::Taiko)).unwrap(), Mods::HD);
+        assert_eq!((Mods::TD | Mods::K4).canonicalize(Some(Mode::Mania)).unwrap(), Mods::K4);
+        assert!((Mods::NC | Mods::HT).canonicalize(None).is_err());
+        assert!(Mods::K4.canonicalize(Some(Mode::Osu)).is_err());
+    }
+
+    #[test]
+    fn difficulty_mods() {
+        assert_eq!((Mods::HD | Mods::NF | Mods::SD | Mods::DT).difficulty_mods(), Mods::HD | Mods::DT);
+        assert_eq!((Mods::V2 | Mods::MR).difficulty_mods(), Mods::NONE);
+    }
+}
@@ -1,52 +1,145 @@
+use std::collections::{HashSet, VecDeque};
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
 use reqwest::{Client, Url};
-use serde_json::json;
 use crate::entity_v1::{Beatmap, GameRecord, Games, MatchRoom, Replay, Scores, User};
-use crate::util::{assembly_data, assembly_user_type, data_serialize, data_serialize_vec, DataType, Mode, UserType};
+use crate::http::{Cache, Http, HttpTransport, RateLimiter, ReqwestTransport, RetryPolicy};
+use crate::util::{Mode, Mods, UserType};
+use crate::v1::request::{GetBeatmaps, GetMatch, GetReplay, GetScores, GetUser, GetUserBest, GetUserRecent, Request};
 use crate::error::{Error, Result};
 
 /// 父url
-pub static OSU_API_1: &'static str = "https://osu.ppy.sh/api";
-/// reqwest
-async fn get(url: Url) -> String {
-    let client = Client::new();
-    let res = client.get(url.to_string())
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .send().await;
-    match res {
-        Ok(response) => {
-            response.text().await.unwrap_or(String::from(""))
-        }
-        Err(err) => {panic!("数据获取错误: {}", err)}
-    }
+pub static OSU_API_1: &str = "https://osu.ppy.sh/api";
+
+/// 爬取谱面时每页的数量
+const CRAWL_PAGE_SIZE: i16 = 500;
+
+/// 爬取谱面的状态
+struct Crawl {
+    since: Option<DateTime<Utc>>,
+    mode: Option<Mode>,
+    /// 上一页的 beatmap_id, 用来去重
+    seen: HashSet<i64>,
+    buffer: VecDeque<Beatmap>,
+    done: bool,
 }
 
 /// # ApiV1
 /// 在 https://osu.ppy.sh/p/api 申请一个API KEY，信息随意填写。
 ///
-/// 限制：每分钟1200次，最高瞬时1400次。
+/// 限制：每分钟1200次，最高瞬时1400次。(默认不限流, 见 [`ApiV1::with_rate_limiter`])
 ///
 /// 共有的父URL：https://osu.ppy.sh/api/
+///
+/// clone 开销很小,clone 出来的客户端共用同一个连接池
+#[derive(Debug, Clone)]
 pub struct ApiV1{
     /// API KEY
     api_key:String,
+    http:Http,
+    /// 父url, 默认 [`OSU_API_1`]
+    base_url:String,
 }
 
 impl ApiV1 {
     /// ```
-    /// use osu_api_rs::api::ApiV1;
     /// use osu_api_rs::ApiV1;
+    /// # const API_KEY: &str = "API_KEY";
     ///
     /// let api_v1 = ApiV1::new(format!("{}", API_KEY));
     /// // 或
     /// let api_v1 = ApiV1::new(API_KEY);
     /// ```
-
     pub fn new(api_key:impl Into<String>) -> ApiV1 {
         ApiV1{
             api_key:api_key.into(),
+            http:Http::default(),
+            base_url:OSU_API_1.to_string(),
+        }
+    }
+    /// 使用自定义的 HTTP 客户端 (超时, User-Agent, 代理等), 见 [`ClientBuilder`](crate::http::ClientBuilder)
+    /// ```
+    /// use osu_api_rs::ApiV1;
+    /// use osu_api_rs::http::ClientBuilder;
+    /// # const API_KEY: &str = "API_KEY";
+    ///
+    /// let client = ClientBuilder::new().user_agent("my-bot/1.0").build().unwrap();
+    /// let api_v1 = ApiV1::with_client(API_KEY, client);
+    /// ```
+    pub fn with_client(api_key:impl Into<String>, client:Client) -> ApiV1 {
+        ApiV1::with_transport(api_key, ReqwestTransport::new(client))
+    }
+    /// 使用自定义的 [`HttpTransport`] 发送请求, 测试时可以使用 [`FakeTransport`](crate::http::FakeTransport)
+    pub fn with_transport(api_key:impl Into<String>, transport:impl HttpTransport + 'static) -> ApiV1 {
+        ApiV1{
+            api_key:api_key.into(),
+            http:Http::new(transport),
+            base_url:OSU_API_1.to_string(),
         }
     }
+    /// 更换父url, 可以用来连接本地的测试服务器, 或者兼容 peppy api 的私服 (例如 Ripple: `https://ripple.moe/api`)
+    /// ```
+    /// use osu_api_rs::ApiV1;
+    /// # const API_KEY: &str = "API_KEY";
+    ///
+    /// let api_v1 = ApiV1::new(API_KEY).with_base_url("https://ripple.moe/api");
+    /// assert_eq!(api_v1.base_url(), "https://ripple.moe/api");
+    /// ```
+    pub fn with_base_url(mut self, base_url:impl Into<String>) -> ApiV1 {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+    /// 当前使用的父url
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    /// 开启限流, clone 出来的客户端共用同一个限流器
+    ///
+    /// 限制：每分钟1200次，最高瞬时1400次, 可以直接使用 [`RateLimiter::v1`]
+    /// ```
+    /// use osu_api_rs::http::RateLimiter;
+    /// # let api = osu_api_rs::ApiV1::new("API_KEY");
+    ///
+    /// let api = api.with_rate_limiter(RateLimiter::v1());
+    /// ```
+    pub fn with_rate_limiter(mut self, limiter:RateLimiter) -> ApiV1 {
+        self.http.set_rate_limiter(Some(limiter));
+        self
+    }
+    /// 当前使用的限流器, 可以通过它查看剩余的请求次数
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.http.rate_limiter()
+    }
+    /// 设置重试策略 (默认遇到 429 / 5xx 时 GET 请求最多尝试 3 次)
+    ///
+    /// clone 的开销很小, 想给单次调用换一个策略可以这样:
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::http::RetryPolicy;
+    /// # let api = osu_api_rs::ApiV1::new("API_KEY");
+    ///
+    /// let data = api.clone().with_retry(RetryPolicy::none()).get_beatmap(Some(252002)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry(mut self, retry:RetryPolicy) -> ApiV1 {
+        self.http.set_retry(retry);
+        self
+    }
+    /// 当前使用的重试策略
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        self.http.retry()
+    }
+    /// 开启响应缓存, clone 出来的客户端共用同一个缓存, 见 [`Cache`]
+    pub fn with_cache(mut self, cache:Cache) -> ApiV1 {
+        self.http.set_cache(Some(cache));
+        self
+    }
+    /// 当前使用的缓存
+    pub fn cache(&self) -> Option<&Cache> {
+        self.http.cache()
+    }
     /// # /api/get_beatmaps
     /// # 谱面信息
     ///
@@ -65,14 +158,31 @@ impl ApiV1 {
     ///
     /// return ：一个包含所有符合指定条件的、ranked谱面的JSON列表。每个难度一个列表。
     /// # Example
-    /// ```
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
     /// use osu_api_rs::ApiV1;
+    /// # const API_KEY: &str = "API_KEY";
     ///
-    /// let api_v1 = ApiV1::new(format!("{}", API KEY));
-    /// let beatmaps = api_v1.get_beatmaps(None,Some(3020923),None,None,true,None,None).await.unwrap();
+    /// let api_v1 = ApiV1::new(format!("{}", API_KEY));
+    /// let beatmaps = api_v1.get_beatmaps(None,Some(3020923),None,None,true,None,None).await?;
     /// println!("{:?}", beatmaps);
+    /// # Ok(())
+    /// # }
     /// ```
-
+    ///
+    /// 参数比较多, 推荐使用 [`GetBeatmaps`]:
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::request_v1::GetBeatmaps;
+    /// # let api_v1 = osu_api_rs::ApiV1::new("API_KEY");
+    ///
+    /// let beatmaps = api_v1.request(GetBeatmaps::new().beatmap(3020923).converted(true)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_beatmaps(
         &self,
         /*
@@ -98,28 +208,93 @@ impl ApiV1 {
         // 返回的数量
         limit:Option<i16>,
     ) -> Result<Vec<Beatmap>> {
-        let mut vec  =  vec![];
-
-        assembly_data(&[
-            ("s", DataType::Int64(set_id)),
-            ("b", DataType::Int64(beatmap_id)),
-            ("m", DataType::Mode(mode,false)),
-            ("h", DataType::String(hash)),
-            ("limit", DataType::Int16(limit)),
-        ],&mut vec);
-
-        if let Some(user) = user {
-            assembly_user_type(user,&mut vec);
-        }
-        if transformation {
-            vec.push(("a", "1".to_string()));
+        let mut req = GetBeatmaps::new().converted(transformation);
+        req.set_id = set_id;
+        req.beatmap_id = beatmap_id;
+        req.user = user;
+        req.mode = mode;
+        req.hash = hash;
+        req.limit = limit;
+        self.request(req).await
+    }
+    /// # 爬取所有 ranked 谱面
+    /// 从 `since` 开始 (None 则从最早的谱面开始), 每次请求 500 个,
+    /// 下一次请求的 `since` 为上一页最后一个谱面的 `approved_date`,
+    /// 页与页之间重叠的谱面会被去重。
+    ///
+    /// 全部谱面大约需要 300+ 次请求, 建议配合 [`ApiV1::with_rate_limiter`] 使用。
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use chrono::{TimeZone, Utc};
+    /// use futures::StreamExt;
+    /// # let api_v1 = osu_api_rs::ApiV1::new("API_KEY");
+    ///
+    /// let since = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+    /// let mut stream = Box::pin(api_v1.crawl_beatmaps(Some(since), None));
+    /// while let Some(beatmap) = stream.next().await {
+    ///     let beatmap = beatmap?;
+    ///     println!("{} - {}", beatmap.beatmap_id, beatmap.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn crawl_beatmaps(&self, since:Option<DateTime<Utc>>, mode:Option<Mode>) -> impl Stream<Item = Result<Beatmap>> + '_ {
+        let state = Crawl {
+            since,
+            mode,
+            seen: HashSet::new(),
+            buffer: VecDeque::new(),
+            done: false,
+        };
+        stream::try_unfold(state, move |mut state| async move {
+            loop {
+                if let Some(beatmap) = state.buffer.pop_front() {
+                    return Ok(Some((beatmap, state)));
+                }
+                if state.done {
+                    return Ok(None);
+                }
+                self.crawl_next_page(&mut state).await?;
+            }
+        })
+    }
+    /// 请求下一页, 放到 buffer 里
+    async fn crawl_next_page(&self, state:&mut Crawl) -> Result<()> {
+        let mut req = GetBeatmaps::new().limit(CRAWL_PAGE_SIZE);
+        req.since = state.since;
+        req.mode = state.mode;
+        let page = match self.request(req).await {
+            Ok(page) => page,
+            Err(Error::Null) => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        let full = page.len() >= CRAWL_PAGE_SIZE as usize;
+        let last_date = match page.last().and_then(|beatmap| beatmap.approved_date) {
+            Some(date) => date,
+            None => {
+                state.buffer.extend(page.into_iter().filter(|beatmap| !state.seen.contains(&beatmap.beatmap_id)));
+                state.done = true;
+                return Ok(());
+            }
+        };
+        let seen = page.iter().map(|beatmap| beatmap.beatmap_id).collect::<HashSet<_>>();
+        state.buffer.extend(page.into_iter().filter(|beatmap| !state.seen.contains(&beatmap.beatmap_id)));
+        state.seen = seen;
+
+        if !full {
+            state.done = true;
+        } else if state.buffer.is_empty() {
+            // 同一秒内 ranked 的谱面超过了一页, 只能跳过这一秒
+            if state.since == Some(last_date) {
+                state.done = true;
+            }
+            state.since = Some(last_date);
         } else {
-            vec.push(("a","0".to_string()));
+            // 往前退一秒, 防止同一秒内的谱面被分页截断后丢失
+            state.since = Some(last_date - chrono::Duration::seconds(1));
         }
-        let url = self.assembly_url("get_beatmaps", vec);
-        let data = get(url).await;
-
-        data_serialize_vec::<Beatmap>(data)
+        Ok(())
     }
     /// 使用 beatmap_id 获取铺面信息
     pub async fn get_beatmap(&self, beatmap_id:Option<i64>,) -> Result<Beatmap> {
@@ -157,12 +332,17 @@ impl ApiV1 {
     ///
     /// 返回值： 包含用户信息的JSON列表。
     /// # Example
-    /// ```
-    /// use osu_api_rs::{ApiV1, UserType};
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::{ApiV1, Mode, UserType};
+    /// # const API_KEY: &str = "API_KEY";
     ///
-    /// let api_v1 = ApiV1::new(format!("{}", API KEY));
-    /// let user = api_v1.get_user(UserType::USERID(18267600), Some(3), None).await;
+    /// let api_v1 = ApiV1::new(format!("{}", API_KEY));
+    /// let user = api_v1.get_user(UserType::USERID(18267600), Some(Mode::Mania), None).await;
     /// println!("{:?}", user);
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn get_user(
         &self,
@@ -173,23 +353,10 @@ impl ApiV1 {
         // 最后成绩的日期,默认 1
         event_days:Option<i8>
     ) -> Result<User> {
-        let mut vec  =  vec![];
-
-        assembly_data(
-            &[
-                ("m",DataType::Mode(mode,false)),
-                ("event_days",DataType::Int8(event_days))
-            ], &mut vec);
-
-        assembly_user_type(user,&mut vec);
-
-        let url = self.assembly_url("get_user", vec);
-        let data = get(url).await;
-        let serialize_vec= data_serialize_vec::<User>(data);
-        match serialize_vec {
-            Err(err) => Err(err),
-            Ok(data) => Ok(data[0].clone())
-        }
+        let mut req = GetUser::new(user);
+        req.mode = mode;
+        req.event_days = event_days;
+        self.request(req).await
     }
 
     /// # /api/get_scores
@@ -201,19 +368,23 @@ impl ApiV1 {
     ///* b - 指定一个谱面的Beatmap ID。（/b/xxxxx）（必须）
     ///* u - 指定一个要返回分数的用户名/用户数字id。
     ///* m - 模式 (0 = osu!, 1 = Taiko, 2 = CtB, 3 = osu!mania)，默认为0。
-    ///* mods -指定一个或者一些mod (具体枚举映射参见后文)
+    ///* mods -指定一个或者一些mod (具体枚举映射参见后文), 只返回 mod 完全一致的成绩, NC / PF 会自动带上 DT / SD
     ///* type - 指定u参数是数字id还是用户名。对于数字id，该参数值为id，而用户名则参数值为string。默认为智能识别，在纯数字用户名时可能出现问题。
     ///* limit - 返回值的数量. 默认值是50，最大值是100。
     ///
     /// 返回值：包含选定谱面前100分数信息的JSON列表。
     /// # Example
-    ///```
+    ///```no_run
+    ///# #[tokio::main]
+    ///# async fn main() -> osu_api_rs::error::Result<()> {
+    ///use osu_api_rs::{ApiV1, Mode, Mods, UserType};
+    ///# const API_KEY: &str = "API_KEY";
     ///
-    /// use osu_api_rs::{ApiV1, UserType};
-    ///
-    /// let api_v1 = ApiV1::new(format!("{}", API KEY));
-    /// let user = api_v1.get_scores(Some(992512),UserType::USERID(18267600),Some(3),Some(1)).await;
-    /// println!("{:?}", user);
+    ///let api_v1 = ApiV1::new(format!("{}", API_KEY));
+    ///let user = api_v1.get_scores(Some(992512),UserType::USERID(18267600),Some(Mode::Osu),Some(Mods::HD | Mods::DT),Some(1)).await;
+    ///println!("{:?}", user);
+    ///# Ok(())
+    ///# }
     /// ```
     pub async fn get_scores(
         &self,
@@ -223,25 +394,20 @@ impl ApiV1 {
         user:UserType<'_>,
         // 游戏模式, (0 = osu!, 1 = Taiko, 2 = CtB, 3 = osu!mania)
         mode:Option<Mode>,
+        // 只返回使用这些 mod 的成绩
+        mods:Option<Mods>,
         // 获取数量
         limit:Option<i8>
     ) -> Result<Vec<Scores>> {
-        let mut vec  =  vec![];
-
-        assembly_data(&[
-            ("m",DataType::Mode(mode,false)),
-            ("limit",DataType::Int8(limit)),
-        ],&mut vec);
-
-        assembly_user_type(user,&mut vec);
-
-        let url = self.assembly_url("get_scores",vec);
-
-        let data = get(url).await;
-        data_serialize_vec::<Scores>(data)
+        let beatmap_id = beatmap_id.ok_or_else(|| Error::InvalidInput("get_scores 需要 beatmap_id".to_string()))?;
+        let mut req = GetScores::new(beatmap_id).user(user);
+        req.mode = mode;
+        req.mods = mods;
+        req.limit = limit;
+        self.request(req).await
     }
     pub async fn get_score(&self, beatmap_id: Option<i64>, user:UserType<'_>, mode:Option<Mode>, ) ->Result<Scores> {
-        let vec = self.get_scores(beatmap_id, user, mode, Some(1)).await;
+        let vec = self.get_scores(beatmap_id, user, mode, None, Some(1)).await;
         match vec {
             Err(err) => Err(err),
             Ok(data) => Ok(data[0].clone())
@@ -260,26 +426,23 @@ impl ApiV1 {
     ///
     /// 返回值：包含了指定用户的BP前10的JSON列表。
     /// # Example
-    ///```
-    /// use osu_api_rs::{ApiV1, UserType};
+    ///```no_run
+    ///# #[tokio::main]
+    ///# async fn main() -> osu_api_rs::error::Result<()> {
+    ///use osu_api_rs::{ApiV1, Mode, UserType};
+    ///# const API_KEY: &str = "API_KEY";
     ///
-    /// let api_v1 = ApiV1::new(format!("{}", API KEY));
-    /// let bp_list = api_v1.get_user_bp_list(UserType::USERID(18267600),Some(3),Some(1)).await;
-    /// println!("{:?}", bp_list);
+    ///let api_v1 = ApiV1::new(format!("{}", API_KEY));
+    ///let bp_list = api_v1.get_user_bp_list(UserType::USERID(18267600),Some(Mode::Mania),Some(1)).await;
+    ///println!("{:?}", bp_list);
+    ///# Ok(())
+    ///# }
     /// ```
     pub async fn get_user_bp_list(&self, user:UserType<'_>, mode:Option<Mode>, limit:Option<i8>) -> Result<Vec<GameRecord>>{
-        let mut vec =  vec![];
-
-        assembly_data(&[
-            ("m",DataType::Mode(mode,false)),
-            ("limit",DataType::Int8(limit)),
-        ],&mut vec);
-
-        assembly_user_type(user,&mut vec);
-
-        let url = self.assembly_url("get_user_best",vec);
-        let data = get(url).await;
-        data_serialize_vec::<GameRecord>(data)
+        let mut req = GetUserBest::new(user);
+        req.mode = mode;
+        req.limit = limit;
+        self.request(req).await
     }
     /// 获取指定 Bp
     pub async fn get_user_bp(
@@ -303,17 +466,10 @@ impl ApiV1 {
     /// * 返回值：包含玩家最近10次游戏记录的JSON列表。
     /// * 字段与BP一致，不再赘述
     pub async fn get_user_recent_list(&self, user:UserType<'_>, mode:Option<Mode>, limit:Option<i8>) -> Result<Vec<GameRecord>> {
-        let mut vec  =  vec![];
-        assembly_data(&[
-            ("m",DataType::Mode(mode,false)),
-            ("limit",DataType::Int8(limit)),
-        ],&mut vec);
-
-        assembly_user_type(user,&mut vec);
-
-        let url = self.assembly_url("get_user_recent",vec);
-        let data = get(url).await;
-        data_serialize_vec::<GameRecord>(data)
+        let mut req = GetUserRecent::new(user);
+        req.mode = mode;
+        req.limit = limit;
+        self.request(req).await
     }
     /// 获取最新游戏记录,(包括失败)?
     pub async fn get_user_recent(
@@ -336,30 +492,28 @@ impl ApiV1 {
     /// * mp - 房间id（必须）【也就是官网MP Link的参数】
     /// * 在房间中使用 !mp settings 获取match
     ///
+    /// ```text
     /// 例: https://osu.ppy.sh/community/matches/105537044
-    ///
-    ///                                             ^
+    ///                                          ^
+    /// ```
     ///
     /// 返回值：包括房间信息和玩家成绩的JSON列表
     /// # Example
-    /// ```
-    /// use osu_api_rs::{ApiV1, UserType};
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::ApiV1;
+    /// # const API_KEY: &str = "API_KEY";
     ///
-    /// let api_v1 = ApiV1::new(format!("{}", API KEY));
+    /// let api_v1 = ApiV1::new(format!("{}", API_KEY));
     /// let room = api_v1.get_match(Some(105537044)).await;
     /// println!("{:?}", room);
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn get_match(&self, mp_id:Option<i64>, ) -> Result<MatchRoom> {
-        let mut vec = vec![];
-
-        assembly_data(&[
-            ("mp", DataType::Int64(mp_id))
-        ],&mut vec);
-
-        let url = self.assembly_url("get_match", vec);
-
-        let data = get(url).await;
-        data_serialize(data)
+        let mp_id = mp_id.ok_or_else(|| Error::InvalidInput("get_match 需要 mp_id".to_string()))?;
+        self.request(GetMatch::new(mp_id)).await
     }
     /// 获取mp最新的成绩
     pub async fn get_match_recent_scores(&self, mp_id:Option<i64>, ) -> Result<Games> {
@@ -367,13 +521,9 @@ impl ApiV1 {
         match match_room {
             Err(err) => Err(err),
             Ok(data) => {
-                let vec = &data.games;
-                return if data.games.len() >= 1 {
-                    Ok(vec[data.games.len() - 1].clone())
-                } else if data.games.len() == 0{
-                    Err(Error::Null)
-                }else {
-                    Ok(vec[0].clone())
+                match data.games.last() {
+                    Some(games) => Ok(games.clone()),
+                    None => Err(Error::Null),
                 }
             }
         }
@@ -395,22 +545,29 @@ impl ApiV1 {
         beatmap_id:Option<i64>,
         user:UserType<'_>,
     ) -> Result<Replay> {
-       let mut vec =  vec![];
-
-        assembly_data(&[
-            ("m",DataType::Mode(mode,false)),
-            ("b",DataType::Int64(beatmap_id)),
-        ],&mut vec);
-
-        assembly_user_type(user,&mut vec);
-
-        let url = self.assembly_url("get_replay", vec);
-
-        let data = get(url).await;
-        data_serialize(data)
+        let mode = mode.ok_or_else(|| Error::InvalidInput("get_replay 需要 mode".to_string()))?;
+        let beatmap_id = beatmap_id.ok_or_else(|| Error::InvalidInput("get_replay 需要 beatmap_id".to_string()))?;
+        self.request(GetReplay::new(mode, beatmap_id, user)).await
+    }
+    /// 发送一个请求, 见 [`request_v1`](crate::request_v1)
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::UserType;
+    /// use osu_api_rs::request_v1::GetUserBest;
+    /// # let api_v1 = osu_api_rs::ApiV1::new("API_KEY");
+    ///
+    /// let bp = api_v1.request(GetUserBest::new(UserType::USERID(18267600)).limit(100)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request<R:Request>(&self, req:R) -> Result<R::Output> {
+        let vec = req.query()?;
+        let url = self.assembly_url(R::ENDPOINT, vec)?;
+        self.http.get(R::ENDPOINT, url, None, |data| req.parse(data)).await
     }
-    fn assembly_url<URL:AsRef<str> + std::fmt::Display>(&self, url: URL, vec:Vec<(&str,String)>) -> Url {
-        Url::parse_with_params(format!("{}/{}?k={}",OSU_API_1,<URL as Into<URL>>::into(url), self.api_key).as_str(),
-                                         &vec).unwrap()
+    fn assembly_url<URL:AsRef<str> + std::fmt::Display>(&self, url: URL, vec:Vec<(&str,String)>) -> Result<Url> {
+        Url::parse_with_params(format!("{}/{}?k={}",self.base_url,<URL as Into<URL>>::into(url), self.api_key).as_str(),
+                                         &vec).map_err(|err| Error::InvalidInput(err.to_string()))
     }
 }
\ No newline at end of file
@@ -1,3 +1,6 @@
+use chrono::{DateTime, Utc};
+use crate::util::{de, Genre, Language, Mode, Mods, RankStatus};
+
 ///  # Beatmap
 /// 引用: https://docs.osuwiki.cn/jin-jie-zhi-lu/wei-rao-osu-kaifa#5.1.1-pu-mian-xin-xi
 ///
@@ -7,7 +10,7 @@
 ///     {
 ///         // 4 = loved, 3 = qualified, 2 = approved, 1 = ranked, 0 = pending, -1 = WIP, -2 = graveyard
 ///         "approved" : "1",
-///          // ranked日期, 时区为UTC+8
+///          // ranked日期, 时区为UTC (中文 wiki 写的 UTC+8 是旧接口的说法)
 ///         "approved_date" : "2013-07-02 01:01:12",
 ///          // 最后更新日期，时区同上。 如果谱面被Unranked之后Reranked，该日期可能晚于上面的日期。
 ///         "last_update" : "2013-07-06 16:51:22",
@@ -44,46 +47,79 @@
 /// ```
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct Beatmap {
-    pub beatmapset_id:String,
-    pub beatmap_id:String,
-    pub approved:String,
-    pub total_length:String,
-    pub hit_length:String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub beatmapset_id:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub beatmap_id:i64,
+    pub approved:RankStatus,
+    #[serde(deserialize_with = "de::from_str")]
+    pub total_length:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub hit_length:i32,
     pub version:String,
     pub file_md5:String,
-    pub diff_size:String,
-    pub diff_overall:String,
-    pub diff_approach:String,
-    pub diff_drain:String,
-    pub mode:String,
-    pub count_normal:String,
-    pub count_slider:String,
-    pub count_spinner:String,
-    pub submit_date:String,
-    pub approved_date:String,
-    pub last_update:String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub diff_size:f32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub diff_overall:f32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub diff_approach:f32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub diff_drain:f32,
+    pub mode:Mode,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count_normal:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count_slider:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count_spinner:i32,
+    #[serde(default, deserialize_with = "de::option_datetime")]
+    pub submit_date:Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "de::option_datetime")]
+    pub approved_date:Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "de::datetime")]
+    pub last_update:DateTime<Utc>,
     pub artist:String,
-    pub artist_unicode:String,
+    #[serde(default)]
+    pub artist_unicode:Option<String>,
     pub title:String,
-    pub title_unicode:String,
+    #[serde(default)]
+    pub title_unicode:Option<String>,
     pub creator:String,
-    pub creator_id:String,
-    pub bpm:String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub creator_id:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub bpm:f32,
     pub source:String,
     pub tags:String,
-    pub genre_id:String,
-    pub language_id:String,
-    pub favourite_count:String,
-    pub rating:String,
-    pub storyboard:String,
-    pub video:String,
-    pub download_unavailable:String,
-    pub audio_unavailable:String,
-    pub playcount:String,
-    pub passcount:String,
-    pub packs:String,
-    pub max_combo:String,
-    pub difficultyrating:String,
+    pub genre_id:Genre,
+    pub language_id:Language,
+    #[serde(deserialize_with = "de::from_str")]
+    pub favourite_count:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub rating:f32,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub storyboard:bool,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub video:bool,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub download_unavailable:bool,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub audio_unavailable:bool,
+    #[serde(deserialize_with = "de::from_str")]
+    pub playcount:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub passcount:i64,
+    #[serde(default)]
+    pub packs:Option<String>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub max_combo:Option<i32>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub diff_aim:Option<f32>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub diff_speed:Option<f32>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub difficultyrating:f32,
 }
 
 ///  # User
@@ -122,28 +158,64 @@ pub struct Beatmap {
 ///
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct User {
-    pub user_id:String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub user_id:i64,
     pub username:String,
-    pub join_date:String,
-    pub count300:String,
-    pub count100:String,
-    pub count50:String,
-    pub playcount:String,
-    pub ranked_score:String,
-    pub total_score:String,
-    pub pp_rank:String,
-    pub level:String,
-    pub pp_raw:String,
-    pub accuracy:String,
-    pub count_rank_ss:String,
-    pub count_rank_ssh:String,
-    pub count_rank_s:String,
-    pub count_rank_sh:String,
-    pub count_rank_a:String,
+    #[serde(deserialize_with = "de::datetime")]
+    pub join_date:DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count300:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count100:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count50:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub playcount:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub ranked_score:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub total_score:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub pp_rank:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub level:Option<f64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub pp_raw:Option<f64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub accuracy:Option<f64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count_rank_ss:Option<i32>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count_rank_ssh:Option<i32>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count_rank_s:Option<i32>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count_rank_sh:Option<i32>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub count_rank_a:Option<i32>,
     pub country:String,
-    pub total_seconds_played:String,
-    pub pp_country_rank:String,
-    pub events:Vec<String>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub total_seconds_played:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub pp_country_rank:Option<i64>,
+    #[serde(default)]
+    pub events:Vec<UserEvent>,
+}
+
+/// 用户的动态, 见 [`User::events`]
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct UserEvent {
+    /// 动态的 html
+    pub display_html:String,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub beatmap_id:Option<i64>,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub beatmapset_id:Option<i64>,
+    #[serde(deserialize_with = "de::datetime")]
+    pub date:DateTime<Utc>,
+    /// 1-32
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub epicfactor:Option<i32>,
 }
 
 ///  # Scores
@@ -177,23 +249,37 @@ pub struct User {
 ///
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct Scores{
-    pub score_id :String,
-    pub score :String,
-    pub username :String,
-    pub maxcombo :String,
-    pub count50 :String,
-    pub count100 :String,
-    pub count300 :String,
-    pub countmiss :String,
-    pub countkatu :String,
-    pub countgeki :String,
-    pub perfect :String,
-    pub enabled_mods :String,
-    pub user_id :String,
-    pub date :String,
-    pub rank :String,
-    pub pp :String,
-    pub replay_available :String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub score_id:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub score:i64,
+    pub username:String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub maxcombo:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count50:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count100:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count300:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countmiss:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countkatu:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countgeki:i32,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub perfect:bool,
+    pub enabled_mods:Mods,
+    #[serde(deserialize_with = "de::from_str")]
+    pub user_id:i64,
+    #[serde(deserialize_with = "de::datetime")]
+    pub date:DateTime<Utc>,
+    pub rank:String,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub pp:Option<f32>,
+    #[serde(default, deserialize_with = "de::bool_from_str")]
+    pub replay_available:bool,
 }
 ///  # best Bp + recent
 /// 引用: https://docs.osuwiki.cn/jin-jie-zhi-lu/wei-rao-osu-kaifa#5.1.4-wan-jia-de-bp
@@ -225,23 +311,38 @@ pub struct Scores{
 ///
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct GameRecord {
-    pub beatmap_id :String,
-    pub score_id :String,
-    pub score :String,
-    pub maxcombo :String,
-    pub count50 :String,
-    pub count100 :String,
-    pub count300 :String,
-    pub countmiss :String,
-    pub countkatu :String,
-    pub countgeki :String,
-    pub perfect :String,
-    pub enabled_mods :String,
-    pub user_id :String,
-    pub date :String,
-    pub rank :String,
-    pub pp :String,
-    pub replay_available :String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub beatmap_id:i64,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub score_id:Option<i64>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub score:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub maxcombo:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count50:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count100:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count300:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countmiss:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countkatu:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countgeki:i32,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub perfect:bool,
+    pub enabled_mods:Mods,
+    #[serde(deserialize_with = "de::from_str")]
+    pub user_id:i64,
+    #[serde(deserialize_with = "de::datetime")]
+    pub date:DateTime<Utc>,
+    pub rank:String,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub pp:Option<f32>,
+    #[serde(default, deserialize_with = "de::bool_from_str")]
+    pub replay_available:bool,
 }
 ///  # match
 /// 引用: https://docs.osuwiki.cn/jin-jie-zhi-lu/wei-rao-osu-kaifa#5.1.6-mp-fang-jian-xin-xi
@@ -292,42 +393,66 @@ pub struct MatchRoom {
 
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct RoomInfo {
-    pub match_id :String,
-    pub name :String,
-    pub start_time :String,
-    pub end_time :Option<String>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub match_id:i64,
+    pub name:String,
+    #[serde(deserialize_with = "de::datetime")]
+    pub start_time:DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::option_datetime")]
+    pub end_time:Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct Games {
-    pub game_id :String,
-    pub start_time :String,
-    pub end_time :String,
-    pub beatmap_id :String,
-    pub play_mode :String,
-    pub match_type :String,
-    pub scoring_type :String,
-    pub team_type :String,
-    pub mods :String,
-    pub scores :Vec<RoomScores>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub game_id:i64,
+    #[serde(deserialize_with = "de::datetime")]
+    pub start_time:DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::option_datetime")]
+    pub end_time:Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub beatmap_id:i64,
+    pub play_mode:Mode,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub match_type:Option<i32>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub scoring_type:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub team_type:i32,
+    pub mods:Mods,
+    pub scores:Vec<RoomScores>,
 }
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct RoomScores {
-    pub slot :String,
-    pub team :String,
-    pub user_id :String,
-    pub score :String,
-    pub maxcombo :String,
-    pub rank :String,
-    pub count50 :String,
-    pub count100 :String,
-    pub count300 :String,
-    pub countmiss :String,
-    pub countgeki :String,
-    pub countkatu :String,
-    pub perfect :String,
-    pub pass :String,
-    pub enabled_mods :Option<String>,
+    #[serde(deserialize_with = "de::from_str")]
+    pub slot:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub team:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub user_id:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub score:i64,
+    #[serde(deserialize_with = "de::from_str")]
+    pub maxcombo:i32,
+    pub rank:String,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count50:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count100:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub count300:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countmiss:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countgeki:i32,
+    #[serde(deserialize_with = "de::from_str")]
+    pub countkatu:i32,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub perfect:bool,
+    #[serde(deserialize_with = "de::bool_from_str")]
+    pub pass:bool,
+    #[serde(default, deserialize_with = "de::option_from_str")]
+    pub enabled_mods:Option<Mods>,
 }
 ///  # replay
 /// 引用: https://docs.osuwiki.cn/jin-jie-zhi-lu/wei-rao-osu-kaifa#5.1.7-huo-qu-hui-fang
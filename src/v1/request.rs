@@ -0,0 +1,418 @@
+//! # 请求构建
+//! 每个接口对应一个请求结构体, 用链式调用设置参数, 发送前会检查参数范围。
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> osu_api_rs::error::Result<()> {
+//! use osu_api_rs::{ApiV1, Mode};
+//! use osu_api_rs::request_v1::GetBeatmaps;
+//! # const API_KEY: &str = "API_KEY";
+//!
+//! let api_v1 = ApiV1::new(API_KEY);
+//! let beatmaps = api_v1.request(GetBeatmaps::new().set(93398).mode(Mode::Osu).limit(10)).await;
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+use crate::entity_v1::{Beatmap, GameRecord, MatchRoom, Replay, Scores, User};
+use crate::error::{Error, Result};
+use crate::util::{de, assembly_data, assembly_user_type, data_serialize, data_serialize_vec, DataType, Genre, Language, Mode, Mods, RankStatus, UserType};
+
+/// v1 的请求
+pub trait Request {
+    /// 返回的数据
+    type Output;
+    /// 接口名, 例: `get_beatmaps`
+    const ENDPOINT: &'static str;
+    /// 检查参数, 组装查询参数 (不包括 api key)
+    fn query(&self) -> Result<Vec<(&'static str, String)>>;
+    /// 解析返回的数据
+    fn parse(&self, data: String) -> Result<Self::Output>;
+}
+
+/// 检查 limit 的范围
+pub(crate) fn check_limit(name: &str, limit: Option<i64>, max: i64) -> Result<()> {
+    match limit {
+        Some(limit) if !(1..=max).contains(&limit) => {
+            Err(Error::InvalidInput(format!("{} 的范围是 1-{}, 实际为 {}", name, max, limit)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 检查 mods 能否同时使用, 以及在 mode 中能否使用, 见 [`Mods::validate`]
+pub(crate) fn check_mods(mods: Option<Mods>, mode: Option<Mode>) -> Result<()> {
+    match mods {
+        Some(mods) => mods.validate(mode),
+        None => Ok(()),
+    }
+}
+
+/// # /api/get_beatmaps
+/// 见 [`ApiV1::get_beatmaps`](crate::ApiV1::get_beatmaps)
+#[derive(Debug, Clone, Default)]
+pub struct GetBeatmaps<'a> {
+    pub(crate) since: Option<DateTime<Utc>>,
+    pub(crate) set_id: Option<i64>,
+    pub(crate) beatmap_id: Option<i64>,
+    pub(crate) user: Option<UserType<'a>>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) converted: bool,
+    pub(crate) hash: Option<&'a str>,
+    pub(crate) limit: Option<i16>,
+    pub(crate) status: Vec<RankStatus>,
+    pub(crate) genre: Vec<Genre>,
+    pub(crate) language: Vec<Language>,
+}
+
+impl<'a> GetBeatmaps<'a> {
+    pub fn new() -> GetBeatmaps<'a> {
+        GetBeatmaps::default()
+    }
+    /// 返回在该日期之后 ranked 的所有谱面, 发送时转换为 MySQL 格式
+    pub fn since(mut self, since: DateTime<Utc>) -> GetBeatmaps<'a> {
+        self.since = Some(since);
+        self
+    }
+    /// 谱面的 SetID
+    pub fn set(mut self, set_id: i64) -> GetBeatmaps<'a> {
+        self.set_id = Some(set_id);
+        self
+    }
+    /// 谱面的 Beatmap ID
+    pub fn beatmap(mut self, beatmap_id: i64) -> GetBeatmaps<'a> {
+        self.beatmap_id = Some(beatmap_id);
+        self
+    }
+    /// 谱师
+    pub fn user(mut self, user: UserType<'a>) -> GetBeatmaps<'a> {
+        self.user = Some(user);
+        self
+    }
+    pub fn mode(mut self, mode: Mode) -> GetBeatmaps<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    /// 是否包括转谱, 只在设置了 mode 并且不为 osu! 时有效
+    pub fn converted(mut self, converted: bool) -> GetBeatmaps<'a> {
+        self.converted = converted;
+        self
+    }
+    /// 谱面文件的 md5
+    pub fn hash(mut self, hash: &'a str) -> GetBeatmaps<'a> {
+        self.hash = Some(hash);
+        self
+    }
+    /// 返回的数量, 1-500
+    pub fn limit(mut self, limit: i16) -> GetBeatmaps<'a> {
+        self.limit = Some(limit);
+        self
+    }
+    /// 只保留该状态的谱面, 可以多次调用, 满足其一即可
+    ///
+    /// v1 没有这个参数, 是在返回后过滤的, 所以返回的数量可能少于 `limit`
+    pub fn status(mut self, status: RankStatus) -> GetBeatmaps<'a> {
+        self.status.push(status);
+        self
+    }
+    /// 只保留该曲风的谱面, 同 [`status`](GetBeatmaps::status) 在返回后过滤
+    pub fn genre(mut self, genre: Genre) -> GetBeatmaps<'a> {
+        self.genre.push(genre);
+        self
+    }
+    /// 只保留该语言的谱面, 同 [`status`](GetBeatmaps::status) 在返回后过滤
+    pub fn language(mut self, language: Language) -> GetBeatmaps<'a> {
+        self.language.push(language);
+        self
+    }
+    /// 谱面是否满足 status / genre / language 过滤条件
+    pub(crate) fn filter(&self, beatmap: &Beatmap) -> bool {
+        (self.status.is_empty() || self.status.contains(&beatmap.approved))
+            && (self.genre.is_empty() || self.genre.contains(&beatmap.genre_id))
+            && (self.language.is_empty() || self.language.contains(&beatmap.language_id))
+    }
+}
+
+impl Request for GetBeatmaps<'_> {
+    type Output = Vec<Beatmap>;
+    const ENDPOINT: &'static str = "get_beatmaps";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_limit("limit", self.limit.map(i64::from), 500)?;
+        let since = self.since.as_ref().map(de::to_mysql);
+        let mut vec = vec![];
+        assembly_data(&[
+            ("since", DataType::String(since.as_deref())),
+            ("s", DataType::Int64(self.set_id)),
+            ("b", DataType::Int64(self.beatmap_id)),
+            ("m", DataType::Mode(self.mode, false)),
+            ("h", DataType::String(self.hash)),
+            ("limit", DataType::Int16(self.limit)),
+        ], &mut vec);
+        if let Some(user) = self.user {
+            assembly_user_type(user, &mut vec);
+        }
+        vec.push(("a", if self.converted { "1" } else { "0" }.to_string()));
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        let mut beatmaps: Vec<Beatmap> = data_serialize_vec(data)?;
+        beatmaps.retain(|beatmap| self.filter(beatmap));
+        Ok(beatmaps)
+    }
+}
+
+/// # /api/get_user
+/// 见 [`ApiV1::get_user`](crate::ApiV1::get_user)
+#[derive(Debug, Clone)]
+pub struct GetUser<'a> {
+    pub(crate) user: UserType<'a>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) event_days: Option<i8>,
+}
+
+impl<'a> GetUser<'a> {
+    pub fn new(user: UserType<'a>) -> GetUser<'a> {
+        GetUser { user, mode: None, event_days: None }
+    }
+    pub fn mode(mut self, mode: Mode) -> GetUser<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    /// 最后成绩的日期, 1-31
+    pub fn event_days(mut self, event_days: i8) -> GetUser<'a> {
+        self.event_days = Some(event_days);
+        self
+    }
+}
+
+impl Request for GetUser<'_> {
+    type Output = User;
+    const ENDPOINT: &'static str = "get_user";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_limit("event_days", self.event_days.map(i64::from), 31)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("m", DataType::Mode(self.mode, false)),
+            ("event_days", DataType::Int8(self.event_days)),
+        ], &mut vec);
+        assembly_user_type(self.user, &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        let mut vec = data_serialize_vec::<User>(data)?;
+        Ok(vec.swap_remove(0))
+    }
+}
+
+/// # /api/get_scores
+/// 见 [`ApiV1::get_scores`](crate::ApiV1::get_scores)
+#[derive(Debug, Clone)]
+pub struct GetScores<'a> {
+    pub(crate) beatmap_id: i64,
+    pub(crate) user: Option<UserType<'a>>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) mods: Option<Mods>,
+    pub(crate) limit: Option<i8>,
+}
+
+impl<'a> GetScores<'a> {
+    pub fn new(beatmap_id: i64) -> GetScores<'a> {
+        GetScores { beatmap_id, user: None, mode: None, mods: None, limit: None }
+    }
+    /// 只返回这个玩家的成绩
+    pub fn user(mut self, user: UserType<'a>) -> GetScores<'a> {
+        self.user = Some(user);
+        self
+    }
+    pub fn mode(mut self, mode: Mode) -> GetScores<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    /// 只返回使用这些 mod 的成绩 (完全一致), NC / PF 会自动带上 DT / SD
+    pub fn mods(mut self, mods: Mods) -> GetScores<'a> {
+        self.mods = Some(mods);
+        self
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetScores<'a> {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Request for GetScores<'_> {
+    type Output = Vec<Scores>;
+    const ENDPOINT: &'static str = "get_scores";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_limit("limit", self.limit.map(i64::from), 100)?;
+        check_mods(self.mods, self.mode)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("b", DataType::Int64(Some(self.beatmap_id))),
+            ("m", DataType::Mode(self.mode, false)),
+            ("mods", DataType::Mods(self.mods.map(|mods| mods.with_implied()), false)),
+            ("limit", DataType::Int8(self.limit)),
+        ], &mut vec);
+        if let Some(user) = self.user {
+            assembly_user_type(user, &mut vec);
+        }
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// # /api/get_user_best
+/// 见 [`ApiV1::get_user_bp_list`](crate::ApiV1::get_user_bp_list)
+#[derive(Debug, Clone)]
+pub struct GetUserBest<'a> {
+    pub(crate) user: UserType<'a>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) limit: Option<i8>,
+}
+
+impl<'a> GetUserBest<'a> {
+    pub fn new(user: UserType<'a>) -> GetUserBest<'a> {
+        GetUserBest { user, mode: None, limit: None }
+    }
+    pub fn mode(mut self, mode: Mode) -> GetUserBest<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetUserBest<'a> {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Request for GetUserBest<'_> {
+    type Output = Vec<GameRecord>;
+    const ENDPOINT: &'static str = "get_user_best";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_limit("limit", self.limit.map(i64::from), 100)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("m", DataType::Mode(self.mode, false)),
+            ("limit", DataType::Int8(self.limit)),
+        ], &mut vec);
+        assembly_user_type(self.user, &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// # /api/get_user_recent
+/// 见 [`ApiV1::get_user_recent_list`](crate::ApiV1::get_user_recent_list)
+#[derive(Debug, Clone)]
+pub struct GetUserRecent<'a> {
+    pub(crate) user: UserType<'a>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) limit: Option<i8>,
+}
+
+impl<'a> GetUserRecent<'a> {
+    pub fn new(user: UserType<'a>) -> GetUserRecent<'a> {
+        GetUserRecent { user, mode: None, limit: None }
+    }
+    pub fn mode(mut self, mode: Mode) -> GetUserRecent<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    /// 返回的数量, 1-50
+    pub fn limit(mut self, limit: i8) -> GetUserRecent<'a> {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Request for GetUserRecent<'_> {
+    type Output = Vec<GameRecord>;
+    const ENDPOINT: &'static str = "get_user_recent";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_limit("limit", self.limit.map(i64::from), 50)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("m", DataType::Mode(self.mode, false)),
+            ("limit", DataType::Int8(self.limit)),
+        ], &mut vec);
+        assembly_user_type(self.user, &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// # /api/get_match
+/// 见 [`ApiV1::get_match`](crate::ApiV1::get_match)
+#[derive(Debug, Clone)]
+pub struct GetMatch {
+    pub(crate) mp_id: i64,
+}
+
+impl GetMatch {
+    pub fn new(mp_id: i64) -> GetMatch {
+        GetMatch { mp_id }
+    }
+}
+
+impl Request for GetMatch {
+    type Output = MatchRoom;
+    const ENDPOINT: &'static str = "get_match";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        Ok(vec![("mp", self.mp_id.to_string())])
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
+
+/// # /api/get_replay
+/// 见 [`ApiV1::get_replay`](crate::ApiV1::get_replay)
+#[derive(Debug, Clone)]
+pub struct GetReplay<'a> {
+    pub(crate) mode: Mode,
+    pub(crate) beatmap_id: i64,
+    pub(crate) user: UserType<'a>,
+}
+
+impl<'a> GetReplay<'a> {
+    pub fn new(mode: Mode, beatmap_id: i64, user: UserType<'a>) -> GetReplay<'a> {
+        GetReplay { mode, beatmap_id, user }
+    }
+}
+
+impl Request for GetReplay<'_> {
+    type Output = Replay;
+    const ENDPOINT: &'static str = "get_replay";
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        let mut vec = vec![];
+        assembly_data(&[
+            ("m", DataType::Mode(Some(self.mode), false)),
+            ("b", DataType::Int64(Some(self.beatmap_id))),
+        ], &mut vec);
+        assembly_user_type(self.user, &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
@@ -1,74 +1,233 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use reqwest::{Client, Url};
-use reqwest::header::HeaderMap;
-use serde_json::{json, Value};
-use crate::entity_v2::{Beatmap, BestBeatmapScores, UserBeatmapScore};
-use crate::util::{assembly_data, data_serialize, data_serialize_vec, DataType, Mode, Mods};
+use tokio::sync::Mutex;
+use crate::entity_v2::{Beatmap, BeatmapPlaycount, Beatmapset, BeatmapsetSearch, BestBeatmapScores, RecentActivity, Score, User, UserBeatmapScore, UserExtended};
+use crate::http::{Cache, Http, HttpTransport, RateLimiter, ReqwestTransport, RetryPolicy};
+use crate::util::{Mode, Mods, RankStatus, UserType};
+use crate::v2::request::{GetBeatmapScores, GetUser, GetUserBeatmapScore, GetUserBeatmapScores, GetUserBeatmapsets, GetUserMostPlayed, GetUserRecentActivity, GetUserScores, GetUsers, LookupBeatmap, Request, SearchBeatmapsets, UserBeatmapsetType, UserScoreType, USERS_PER_REQUEST};
 use crate::error::{Error, Result};
+use crate::v2::oauth::{self, Credentials, Scope, Token, TokenStore, OSU_API_2_AUTHORIZE, REFRESH_MARGIN};
 /// 父url
-pub static OSU_API_2: &'static str = "https://osu.ppy.sh/api/v2";
+pub static OSU_API_2: &str = "https://osu.ppy.sh/api/v2";
 /// 客户端凭据授予 (没有关联用户权限)
 ///
 /// osu官方文档说明: https://osu.ppy.sh/docs/index.html#authorization-code-grant
-pub static OSU_API_2_OAUTH: &'static str = "https://osu.ppy.sh/oauth/token";
-/// reqwest
-async fn get(url: Url,access_token:&String) -> String {
-    let client = Client::new();
-    let res = client.get(url.to_string())
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .header("Authorization", format!("Bearer {}",access_token))
-        .send().await;
-    match res {
-        Ok(response) => {
-            response.text().await.unwrap_or(String::from(""))
-        }
-        Err(err) => {panic!("数据获取错误: {}", err)}
-    }
-}
-async fn post(url: Url,map:Value,access_token:&String) -> String {
-    let client = Client::new();
-    let res = client.post(url.to_string())
-        .json(&map)
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .header("Authorization", format!("Bearer {}",access_token))
-        .send().await;
-    match res {
-        Ok(response) => {
-            response.text().await.unwrap_or(String::from(""))
-        }
-        Err(err) => {panic!("数据获取错误: {}", err)}
-    }
+pub static OSU_API_2_OAUTH: &str = "https://osu.ppy.sh/oauth/token";
+
+/// 翻页时每页的数量
+const PAGE_SIZE: i8 = 100;
+/// 批量获取用户时最多同时发送的请求数
+const USERS_CONCURRENCY: usize = 4;
+
+/// 按 offset 翻页的状态
+struct Pages<T> {
+    offset: i64,
+    buffer: VecDeque<T>,
+    done: bool,
 }
 
 /// 在 https://osu.ppy.sh/home/account/edit  申请一个新的 OAuth 应用
 ///
-///应用回调链接 随便填写
+///应用回调链接 随便填写 (授权码模式需要和 `redirect_uri` 一致, 见 [`oauth_v2`](crate::oauth_v2))
 /// 我们只需要
 /// * 客户端 ID
 /// * 客户端密钥
 ///
 /// 就可以了
-#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
-/// 结构体里面是 返回的数据
+/// token 自动刷新: 到期前 (见 [`REFRESH_MARGIN`](crate::oauth_v2::REFRESH_MARGIN)) 或者服务器返回 401 时刷新,
+/// 有 refresh token 时用它刷新, 否则重新使用客户端凭据获取。
+///
+/// clone 出来的客户端共用同一个 token, 同时只会有一个刷新请求
+#[derive(Debug, Clone)]
 pub struct ApiV2 {
-    /// 认证类型: Bearer
-    token_type:String,
-    /// 到期时间 1天刷新一次
-    expires_in:i32,
-    /// token
-    access_token:String,
+    auth:Arc<Auth>,
+    /// clone 出来的客户端共用同一个连接池
+    http:Http,
+    /// 父url, 默认 [`OSU_API_2`]
+    base_url:String,
+}
+
+/// token 以及刷新 token 需要的信息
+#[derive(Debug)]
+struct Auth {
+    credentials:Credentials,
+    token:RwLock<Token>,
+    /// 获取 / 刷新 token 后保存到这里
+    store:Option<Arc<dyn TokenStore>>,
+    /// 保证同时只有一个刷新请求
+    refreshing:Mutex<()>,
+}
+
+/// # ApiV2Builder
+/// 创建 `ApiV2` 时的配置
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> osu_api_rs::error::Result<()> {
+/// use osu_api_rs::ApiV2;
+/// # let (client_id, client_secret) = ("1", "secret");
+///
+/// let api_v2 = ApiV2::builder(client_id, client_secret)
+///     .base_url("http://127.0.0.1:8080/api/v2")
+///     .token_url("http://127.0.0.1:8080/oauth/token")
+///     .build().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ApiV2Builder {
+    /// client_id / client_secret / 获取 token 的url (默认 [`OSU_API_2_OAUTH`]) / 申请的权限 (默认 `public`)
+    credentials:Credentials,
+    http:Http,
+    /// 父url, 默认 [`OSU_API_2`]
+    base_url:String,
+    /// 用户授权页面, 默认 [`OSU_API_2_AUTHORIZE`]
+    authorize_url:String,
+    /// 授权码模式的回调地址, 需要和应用设置中的一致
+    redirect_uri:Option<String>,
+    /// token 的保存方式
+    store:Option<Arc<dyn TokenStore>>,
+}
+
+impl ApiV2Builder {
+    /// 使用自定义的 HTTP 客户端, 见 [`ClientBuilder`](crate::http::ClientBuilder)
+    pub fn client(mut self, client:Client) -> ApiV2Builder {
+        self.http = Http::new(ReqwestTransport::new(client));
+        self
+    }
+    /// 使用自定义的 [`HttpTransport`]
+    pub fn transport(mut self, transport:impl HttpTransport + 'static) -> ApiV2Builder {
+        self.http = Http::new(transport);
+        self
+    }
+    /// api 的父url, 例: `https://osu.ppy.sh/api/v2`
+    pub fn base_url(mut self, base_url:impl Into<String>) -> ApiV2Builder {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+    /// 获取 token 的url, 例: `https://osu.ppy.sh/oauth/token`
+    pub fn token_url(mut self, token_url:impl Into<String>) -> ApiV2Builder {
+        self.credentials.token_url = token_url.into();
+        self
+    }
+    /// 用户授权页面, 例: `https://osu.ppy.sh/oauth/authorize`
+    pub fn authorize_url(mut self, authorize_url:impl Into<String>) -> ApiV2Builder {
+        self.authorize_url = authorize_url.into();
+        self
+    }
+    /// 授权码模式的回调地址, 需要和应用设置中的 "应用回调链接" 一致
+    pub fn redirect_uri(mut self, redirect_uri:impl Into<String>) -> ApiV2Builder {
+        self.redirect_uri = Some(redirect_uri.into());
+        self
+    }
+    /// 申请的权限, 见 [`Scope`]
+    ///
+    /// 客户端凭据只能使用 `public` (机器人账号还可以使用 `delegate`), 其他的需要授权码模式
+    pub fn scopes(mut self, scopes:impl IntoIterator<Item = Scope>) -> ApiV2Builder {
+        self.credentials.scopes = scopes.into_iter().collect();
+        self
+    }
+    /// # 授权链接
+    /// 让用户在浏览器中打开, 同意后会跳转到 `redirect_uri?code=...&state=...`,
+    /// 然后用 [`build_with_code`](ApiV2Builder::build_with_code) 换取 token, 见 [`oauth_v2`](crate::oauth_v2)
+    ///
+    /// `state` 可以用 [`new_state`](crate::oauth_v2::new_state) 生成, 回调时需要检查是否一致
+    pub fn authorization_url(&self, state:&str) -> Result<Url> {
+        let redirect_uri = self.redirect_uri.as_deref()
+            .ok_or_else(|| Error::InvalidInput("授权码模式需要设置 redirect_uri".to_string()))?;
+        oauth::authorization_url(&self.authorize_url, &self.credentials.client_id, redirect_uri, &self.credentials.scopes, state)
+    }
+
+    /// 保存 token, 重启后不需要重新授权, 见 [`TokenStore`]
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::ApiV2;
+    /// use osu_api_rs::oauth_v2::FileTokenStore;
+    /// # let (client_id, client_secret) = ("1", "secret");
+    ///
+    /// let api_v2 = ApiV2::builder(client_id, client_secret)
+    ///     .token_store(FileTokenStore::new("token.json"))
+    ///     .build().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn token_store(mut self, store:impl TokenStore + 'static) -> ApiV2Builder {
+        self.store = Some(Arc::new(store));
+        self
+    }
+    /// # 客户端凭据
+    /// 设置了 [`token_store`](ApiV2Builder::token_store) 并且其中有可以使用的 token 时直接使用它
+    /// (过期的话会在第一次请求时刷新), 否则使用客户端凭据获取一个新的 token 并保存。
+    ///
+    /// client_id / client_secret 错误时返回 [`Error::Auth`]
+    pub async fn build(self) -> Result<ApiV2> {
+        if let Some(token) = self.stored_token()? {
+            return Ok(self.finish(token));
+        }
+        let token = self.credentials.client_credentials(&self.http).await?;
+        self.save(&token)?;
+        Ok(self.finish(token))
+    }
+    /// # 授权码模式
+    /// 用回调中的 `code` 换取 access token 和 refresh token, 得到的客户端可以使用用户权限的接口
+    pub async fn build_with_code(self, code:&str) -> Result<ApiV2> {
+        let redirect_uri = self.redirect_uri.as_deref()
+            .ok_or_else(|| Error::InvalidInput("授权码模式需要设置 redirect_uri".to_string()))?;
+        let token = self.credentials.authorization_code(&self.http, code, redirect_uri).await?;
+        self.save(&token)?;
+        Ok(self.finish(token))
+    }
+    /// 使用之前保存的 token (见 [`ApiV2::token`]), 不会发送请求, 过期的话会在第一次请求时刷新
+    pub fn build_with_token(self, token:Token) -> ApiV2 {
+        self.finish(token)
+    }
+    /// store 中的 token, 权限不够或者已经过期又不能刷新的 token 不使用
+    fn stored_token(&self) -> Result<Option<Token>> {
+        let token = match &self.store {
+            Some(store) => store.load()?,
+            None => None,
+        };
+        Ok(token.filter(|token| {
+            let scopes = token.scopes.is_empty() || self.credentials.scopes.iter().all(|scope| token.scopes.contains(scope));
+            scopes && (token.refresh_token.is_some() || !token.expires_within(REFRESH_MARGIN))
+        }))
+    }
+    fn save(&self, token:&Token) -> Result<()> {
+        match &self.store {
+            Some(store) => store.save(token),
+            None => Ok(()),
+        }
+    }
+    fn finish(self, token:Token) -> ApiV2 {
+        ApiV2 {
+            auth:Arc::new(Auth {
+                credentials:self.credentials,
+                token:RwLock::new(token),
+                store:self.store,
+                refreshing:Mutex::new(()),
+            }),
+            http:self.http,
+            base_url:self.base_url,
+        }
+    }
 }
 
 impl ApiV2 {
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
     /// use osu_api_rs::ApiV2;
-    /// let api_v1 = ApiV2::new(client_id,client_secret);
+    /// # let (client_id, client_secret) = (1, "secret");
+    /// let api_v2 = ApiV2::new(client_id.to_string(),client_secret).await?;
     /// // 或
-    /// let api_v1 = ApiV2::new(format!("{}",client_id),format!("{}",client_secret));
+    /// let api_v2 = ApiV2::new(format!("{}",client_id),format!("{}",client_secret)).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// * 客户端凭证  唯一值: client_credentials
@@ -87,20 +246,166 @@ impl ApiV2 {
     ///
     ///     scope:public
     ///
-
-    pub async fn new(client_id:impl Into<String>, client_secret:impl Into<String>) -> ApiV2 {
-        let json = json!({
-	        "grant_type": "client_credentials",
-	        "client_id": client_id.into(),
-	        "client_secret": client_secret.into(),
-	        "scope": "public"
-        });
-        let client = Client::new();
-        let data = client.post(OSU_API_2_OAUTH)
-            .header("Content-Type", "application/json")
-            .json(&json).send().await.unwrap().text().await.unwrap();
-        println!("{}",&data);
-        serde_json::from_str::<ApiV2>(data.as_str()).unwrap()
+    /// client_id / client_secret 错误时返回 [`Error::Auth`]
+    pub async fn new(client_id:impl Into<String>, client_secret:impl Into<String>) -> Result<ApiV2> {
+        ApiV2::builder(client_id, client_secret).build().await
+    }
+    /// 需要自定义 父url / HTTP 客户端 时使用, 见 [`ApiV2Builder`]
+    pub fn builder(client_id:impl Into<String>, client_secret:impl Into<String>) -> ApiV2Builder {
+        ApiV2Builder {
+            credentials:Credentials {
+                client_id:client_id.into(),
+                client_secret:client_secret.into(),
+                token_url:OSU_API_2_OAUTH.to_string(),
+                scopes:vec![Scope::Public],
+            },
+            http:Http::default(),
+            base_url:OSU_API_2.to_string(),
+            authorize_url:OSU_API_2_AUTHORIZE.to_string(),
+            redirect_uri:None,
+            store:None,
+        }
+    }
+    /// 使用之前保存的 token, 不会发送请求, 见 [`ApiV2Builder::build_with_token`]
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::ApiV2;
+    /// # let (client_id, client_secret) = ("1", "secret");
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let saved = serde_json::to_string(&api_v2.token())?;
+    /// // 重启后
+    /// let api_v2 = ApiV2::from_token(client_id, client_secret, serde_json::from_str(&saved)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_token(client_id:impl Into<String>, client_secret:impl Into<String>, token:Token) -> ApiV2 {
+        ApiV2::builder(client_id, client_secret).build_with_token(token)
+    }
+    /// 使用自定义的 HTTP 客户端 (超时, User-Agent, 代理等), 见 [`ClientBuilder`](crate::http::ClientBuilder)
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::ApiV2;
+    /// use osu_api_rs::http::ClientBuilder;
+    /// # let (client_id, client_secret) = ("1", "secret");
+    ///
+    /// let client = ClientBuilder::new().user_agent("my-bot/1.0").build().unwrap();
+    /// let api_v2 = ApiV2::with_client(client_id, client_secret, client).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_client(client_id:impl Into<String>, client_secret:impl Into<String>, client:Client) -> Result<ApiV2> {
+        ApiV2::builder(client_id, client_secret).client(client).build().await
+    }
+    /// 使用自定义的 [`HttpTransport`] 发送请求, 测试时可以使用 [`FakeTransport`](crate::http::FakeTransport)
+    pub async fn with_transport(client_id:impl Into<String>, client_secret:impl Into<String>, transport:impl HttpTransport + 'static) -> Result<ApiV2> {
+        ApiV2::builder(client_id, client_secret).transport(transport).build().await
+    }
+    /// 当前使用的父url
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    /// 申请的权限
+    pub fn scopes(&self) -> &[Scope] {
+        &self.auth.credentials.scopes
+    }
+    /// 当前的 token (access token, refresh token, 到期时间, 权限), 可以保存起来,
+    /// 重启后用 [`ApiV2::from_token`] 继续使用, 也可以设置 [`ApiV2Builder::token_store`] 自动保存
+    pub fn token(&self) -> Token {
+        self.auth.token.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+    /// 当前 token 的到期时间
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.auth.token.read().unwrap_or_else(|err| err.into_inner()).expires_at()
+    }
+    /// 立即刷新 token
+    pub async fn refresh(&self) -> Result<()> {
+        let stale = self.token().access_token;
+        self.refresh_if_stale(&stale).await.map(|_| ())
+    }
+    /// 可以使用的 access token, 快要过期时先刷新
+    async fn access_token(&self) -> Result<String> {
+        let token = self.token();
+        if token.expires_within(REFRESH_MARGIN) {
+            self.refresh_if_stale(&token.access_token).await
+        } else {
+            Ok(token.access_token)
+        }
+    }
+    /// 刷新 token, `stale` 为调用者认为已经失效的 access token
+    ///
+    /// 拿到锁之后 token 已经变了, 说明其他任务刚刚刷新过, 直接使用新的
+    async fn refresh_if_stale(&self, stale:&str) -> Result<String> {
+        let _guard = self.auth.refreshing.lock().await;
+        let current = self.token();
+        if current.access_token != stale {
+            return Ok(current.access_token);
+        }
+        let credentials = &self.auth.credentials;
+        let token = match &current.refresh_token {
+            Some(refresh_token) => credentials.refresh(&self.http, refresh_token).await?,
+            None => credentials.client_credentials(&self.http).await?,
+        };
+        let access_token = token.access_token.clone();
+        *self.auth.token.write().unwrap_or_else(|err| err.into_inner()) = token.clone();
+        // 新的 token 已经生效, 保存失败时仍然返回错误, 避免 refresh token 丢失而不知道
+        if let Some(store) = &self.auth.store {
+            store.save(&token)?;
+        }
+        Ok(access_token)
+    }
+    /// 开启限流, clone 出来的客户端共用同一个限流器
+    ///
+    /// 官方建议不要超过每分钟60次
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::http::RateLimiter;
+    /// # let api = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let api = api.with_rate_limiter(RateLimiter::new(60, 60));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_rate_limiter(mut self, limiter:RateLimiter) -> ApiV2 {
+        self.http.set_rate_limiter(Some(limiter));
+        self
+    }
+    /// 当前使用的限流器, 可以通过它查看剩余的请求次数
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.http.rate_limiter()
+    }
+    /// 设置重试策略 (默认遇到 429 / 5xx 时 GET 请求最多尝试 3 次)
+    ///
+    /// clone 的开销很小, 想给单次调用换一个策略可以这样:
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::http::RetryPolicy;
+    /// # let api = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let data = api.clone().with_retry(RetryPolicy::none()).lookup_beatmap(252002).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry(mut self, retry:RetryPolicy) -> ApiV2 {
+        self.http.set_retry(retry);
+        self
+    }
+    /// 当前使用的重试策略
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        self.http.retry()
+    }
+    /// 开启响应缓存, clone 出来的客户端共用同一个缓存, 见 [`Cache`]
+    pub fn with_cache(mut self, cache:Cache) -> ApiV2 {
+        self.http.set_cache(Some(cache));
+        self
+    }
+    /// 当前使用的缓存
+    pub fn cache(&self) -> Option<&Cache> {
+        self.http.cache()
     }
     /// # Beatmaps
     /// ## GET /beatmaps/lookup
@@ -111,15 +416,7 @@ impl ApiV2 {
     /// * filename  - 可选  要查找的文件名。
     /// * id  - 可选   要查找的 beatmap ID。
     pub async fn lookup_beatmap_all(&self, checksum:Option<&str>, filename:Option<&str>, beatmap_id:Option<i64>) -> Result<Beatmap>  {
-        let mut vec = vec![];
-        assembly_data(&[
-            ("id",DataType::Int64(beatmap_id)),
-            ("checksum",DataType::String(checksum)),
-            ("filename",DataType::String(filename)),
-        ],&mut vec);
-        let url = self.assembly_url("beatmaps/lookup", vec);
-        let data = get(url, &self.access_token).await;
-        data_serialize(data)
+        self.request(LookupBeatmap { checksum, filename, beatmap_id }).await
     }
     /// 通过 beatmap ID 查找
     pub async fn lookup_beatmap(&self, beatmap_id:i64) -> Result<Beatmap>  {
@@ -133,24 +430,15 @@ impl ApiV2 {
     /// ## Query Parameters
     ///
     /// * mode - 可选 (游戏模式:  fruits , mania , osu , taiko )
-    /// * mods - 可选 (匹配Mod的数组 [["DT","MR"]] )
+    /// * mods - 可选 (匹配的 Mods, 例: `Mods::DT | Mods::MR`)
     ///
     ///
     /// return : UserBeatmapScore
-    pub async fn get_user_beatmap_score_complete(&self, beatmap_id:i64,user_id:i64,mode:Option<Mode>,mods:Option<Vec<Mods>>) -> Result<UserBeatmapScore>  {
-        let mut vec = vec![];
-
-        assembly_data(&[
-            ("mode",DataType::Mode(mode,true)),
-            ("mods",DataType::Mods(mods)),
-        ],&mut vec);
-
-        let url = self.assembly_url(
-            format!("beatmaps/{}/scores/users/{}",beatmap_id,user_id),
-            vec);
-        println!("{:?}", &url.to_string());
-        let data = get(url, &self.access_token).await;
-        data_serialize(data)
+    pub async fn get_user_beatmap_score_complete(&self, beatmap_id:i64,user_id:i64,mode:Option<Mode>,mods:Option<Mods>) -> Result<UserBeatmapScore>  {
+        let mut req = GetUserBeatmapScore::new(beatmap_id, user_id);
+        req.mode = mode;
+        req.mods = mods;
+        self.request(req).await
     }
     /// 通过 beatmap_id  /  user_id 获取用户Beatmap分数
     pub async fn get_user_beatmap_score(&self, beatmap_id:i64,user_id:i64) -> Result<UserBeatmapScore>  {
@@ -164,22 +452,14 @@ impl ApiV2 {
     /// ## Query Parameters
     ///
     /// * mode - 可选 (游戏模式:  fruits , mania , osu , taiko )
-    /// * mods - 可选 (匹配Mod的数组 [["DT","MR"]] )
+    /// * mods - 可选 (匹配的 Mods, 例: `Mods::DT | Mods::MR`)
     ///
     /// return : Vec\<UserBeatmapScore\>
-    pub async fn get_user_beatmap_scores_complete_all(&self, beatmap_id:i64,user_id:i64,mode:Option<Mode>,mods:Option<Vec<Mods>>) -> Result<Vec<UserBeatmapScore>>  {
-        let mut vec = vec![];
-
-        assembly_data(&[
-            ("mode",DataType::Mode(mode,true)),
-            ("mods",DataType::Mods(mods)),
-        ],&mut vec);
-
-        let url = self.assembly_url(
-            format!("beatmaps/{}/scores/users/{}/all",beatmap_id,user_id),
-            vec);
-        let data = get(url, &self.access_token).await;
-        data_serialize_vec(data)
+    pub async fn get_user_beatmap_scores_complete_all(&self, beatmap_id:i64,user_id:i64,mode:Option<Mode>,mods:Option<Mods>) -> Result<Vec<UserBeatmapScore>>  {
+        let mut req = GetUserBeatmapScores::new(beatmap_id, user_id);
+        req.mode = mode;
+        req.mods = mods;
+        self.request(req).await
     }
     /// 通过 beatmap_id  /  user_id 获取用户Beatmap全部分数
     pub async fn get_user_beatmap_scores_all(&self, beatmap_id:i64,user_id:i64) -> Result<Vec<UserBeatmapScore>>  {
@@ -192,28 +472,272 @@ impl ApiV2 {
     /// ## Query Parameters
     ///
     /// * mode - 可选 (游戏模式:  fruits , mania , osu , taiko )
-    /// * mods - 可选 (匹配Mod的数组 [["DT","MR"]] )
+    /// * mods - 可选 (匹配的 Mods, 例: `Mods::DT | Mods::MR`)
     /// * type - 可选 (Beatmap得分排名类型)
     ///
     /// return : BestBeatmapScores
-    pub async fn get_beatmap_score(&self, beatmap_id:i64,mode:Option<Mode>,mods:Option<Vec<Mods>>,beatmap_type:Option<&str>) -> Result<BestBeatmapScores>{
-        let mut vec = vec![];
-
-        assembly_data(&[
-            ("mode",DataType::Mode(mode,false)),
-            ("mods",DataType::Mods(mods)),
-            ("type",DataType::String(beatmap_type)),
-        ],&mut vec);
-
-        let url = self.assembly_url(
-            format!("beatmaps/{}/scores",beatmap_id),
-            vec);
-        let data = get(url, &self.access_token).await;
-        data_serialize(data)
+    pub async fn get_beatmap_score(&self, beatmap_id:i64,mode:Option<Mode>,mods:Option<Mods>,beatmap_type:Option<&str>) -> Result<BestBeatmapScores>{
+        let mut req = GetBeatmapScores::new(beatmap_id);
+        req.mode = mode;
+        req.mods = mods;
+        req.ranking_type = beatmap_type;
+        self.request(req).await
     }
-
-    fn assembly_url<URL:AsRef<str> + std::fmt::Display>(&self, url: URL, vec:Vec<(&str,String)>) -> Url {
-        Url::parse_with_params(format!("{}/{}",OSU_API_2,<URL as Into<URL>>::into(url)).as_str(),
-                               &vec).unwrap()
+    /// # 搜索谱面
+    /// ## GET /beatmapsets/search
+    ///
+    /// * query - 可选  搜索关键字
+    /// * status - 可选  谱面状态, 默认只返回有排行榜的谱面
+    ///
+    /// 更多过滤条件 (mode, genre, language, 翻页) 见 [`SearchBeatmapsets`]
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::{Genre, Language, RankStatus};
+    /// use osu_api_rs::request_v2::SearchBeatmapsets;
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let result = api_v2.request(SearchBeatmapsets::new().status(RankStatus::Loved).genre(Genre::Anime).language(Language::Japanese)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_beatmapsets(&self, query:Option<&str>, status:Option<RankStatus>) -> Result<BeatmapsetSearch> {
+        let mut req = SearchBeatmapsets::new();
+        req.query = query;
+        req.status = status;
+        self.request(req).await
+    }
+    /// # 获取用户信息
+    /// ## GET /users/{user}/{mode}
+    ///
+    /// * user - 用户 id 或者用户名 (用户名会加上 `@` 前缀)
+    /// * mode - 可选  统计数据的模式, 默认为用户设置的模式
+    ///
+    /// return : UserExtended
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::{Mode, UserType};
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let user = api_v2.get_user(UserType::USERNAME("peppy"), Some(Mode::Taiko)).await?;
+    /// println!("{} #{:?}", user.username, user.statistics.global_rank);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_user(&self, user:UserType<'_>, mode:Option<Mode>) -> Result<UserExtended> {
+        let mut req = GetUser::new(user);
+        req.mode = mode;
+        self.request(req).await
+    }
+    /// # 批量获取用户
+    /// ## GET /users
+    ///
+    /// * ids - 用户 id, 数量不限, 每 [`USERS_PER_REQUEST`](crate::request_v2::USERS_PER_REQUEST) 个一组, 最多同时请求 4 组 (受 [`RateLimiter`] 限制)
+    ///
+    /// return : 和 ids 的顺序一致, 不存在 (或者被封禁) 的用户为 None, 有一组失败时返回错误
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    /// let ids = [2, 18267600, 0];
+    /// let users = api_v2.get_users(&ids).await?;
+    /// for (id, user) in ids.iter().zip(users) {
+    ///     match user {
+    ///         Some(user) => println!("{}: {}", id, user.username),
+    ///         None => println!("{}: 不存在", id),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_users(&self, ids:&[i64]) -> Result<Vec<Option<User>>> {
+        let chunks = ids.chunks(USERS_PER_REQUEST)
+            .map(|chunk| self.request(GetUsers::new(chunk.iter().copied())));
+        let users:HashMap<i64, User> = stream::iter(chunks)
+            .buffered(USERS_CONCURRENCY)
+            .try_collect::<Vec<_>>().await?
+            .into_iter()
+            .flatten()
+            .map(|user| (user.id, user))
+            .collect();
+        Ok(ids.iter().map(|id| users.get(id).cloned()).collect())
+    }
+    /// # 获取用户的成绩
+    /// ## GET /users/{user}/scores/{type}
+    /// ## URL Parameters
+    /// * user_id - 用户 id
+    /// * score_type - best / firsts / recent / pinned
+    ///
+    /// ## Query Parameters
+    /// * mode - 可选  默认为用户设置的模式
+    /// * include_fails - 可选  是否包括失败的成绩, 只对 recent 有效
+    /// * limit - 可选  1-100
+    /// * offset - 可选  翻页
+    ///
+    /// return : Vec\<Score\>, 没有成绩时返回 [`Error::Null`]
+    pub async fn get_user_scores(&self, user_id:i64, score_type:UserScoreType, mode:Option<Mode>, include_fails:Option<bool>, limit:Option<i8>, offset:Option<i64>) -> Result<Vec<Score>> {
+        let mut req = GetUserScores::new(user_id, score_type);
+        req.mode = mode;
+        req.include_fails = include_fails;
+        req.limit = limit;
+        req.offset = offset;
+        self.request(req).await
+    }
+    /// # 获取用户所有第一名的成绩
+    /// 每次请求 100 个, 直到返回的数量不足一页。
+    /// 有几千个第一名的用户需要几十次请求, 建议配合 [`ApiV2::with_rate_limiter`] 使用。
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use futures::StreamExt;
+    /// use osu_api_rs::Mode;
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let mut stream = Box::pin(api_v2.crawl_user_firsts(18267600, Some(Mode::Osu)));
+    /// while let Some(score) = stream.next().await {
+    ///     let score = score?;
+    ///     println!("{} - {}", score.beatmap.id, score.score);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn crawl_user_firsts(&self, user_id:i64, mode:Option<Mode>) -> impl Stream<Item = Result<Score>> + '_ {
+        self.crawl_pages(move |offset| {
+            let mut req = GetUserScores::new(user_id, UserScoreType::Firsts).limit(PAGE_SIZE).offset(offset);
+            req.mode = mode;
+            req
+        })
+    }
+    /// # 获取用户的谱面
+    /// ## GET /users/{user}/beatmapsets/{type}
+    /// ## URL Parameters
+    /// * user_id - 用户 id
+    /// * set_type - favourite / graveyard / guest / loved / nominated / pending / ranked
+    ///
+    /// ## Query Parameters
+    /// * limit - 可选  1-100
+    /// * offset - 可选  翻页
+    ///
+    /// most_played 见 [`ApiV2::get_user_most_played`]
+    ///
+    /// return : Vec\<Beatmapset\>, 没有谱面时返回 [`Error::Null`]
+    pub async fn get_user_beatmapsets(&self, user_id:i64, set_type:UserBeatmapsetType, limit:Option<i8>, offset:Option<i64>) -> Result<Vec<Beatmapset>> {
+        let mut req = GetUserBeatmapsets::new(user_id, set_type);
+        req.limit = limit;
+        req.offset = offset;
+        self.request(req).await
+    }
+    /// # 获取用户玩过最多次的谱面
+    /// ## GET /users/{user}/beatmapsets/most_played
+    ///
+    /// return : Vec\<BeatmapPlaycount\>, 按游玩次数从多到少, 没有时返回 [`Error::Null`]
+    pub async fn get_user_most_played(&self, user_id:i64, limit:Option<i8>, offset:Option<i64>) -> Result<Vec<BeatmapPlaycount>> {
+        let mut req = GetUserMostPlayed::new(user_id);
+        req.limit = limit;
+        req.offset = offset;
+        self.request(req).await
+    }
+    /// # 获取用户全部的谱面
+    /// 同 [`ApiV2::get_user_beatmapsets`], 每次请求 100 个, 直到返回的数量不足一页
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use futures::StreamExt;
+    /// use osu_api_rs::request_v2::UserBeatmapsetType;
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let mut stream = Box::pin(api_v2.crawl_user_beatmapsets(2, UserBeatmapsetType::Favourite));
+    /// while let Some(beatmapset) = stream.next().await {
+    ///     let beatmapset = beatmapset?;
+    ///     println!("{} - {}", beatmapset.artist, beatmapset.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn crawl_user_beatmapsets(&self, user_id:i64, set_type:UserBeatmapsetType) -> impl Stream<Item = Result<Beatmapset>> + '_ {
+        self.crawl_pages(move |offset| GetUserBeatmapsets::new(user_id, set_type).limit(PAGE_SIZE).offset(offset))
+    }
+    /// # 获取用户玩过的全部谱面
+    /// 同 [`ApiV2::get_user_most_played`], 每次请求 100 个, 直到返回的数量不足一页
+    pub fn crawl_user_most_played(&self, user_id:i64) -> impl Stream<Item = Result<BeatmapPlaycount>> + '_ {
+        self.crawl_pages(move |offset| GetUserMostPlayed::new(user_id).limit(PAGE_SIZE).offset(offset))
+    }
+    /// # 获取用户最近的动态
+    /// ## GET /users/{user}/recent_activity
+    ///
+    /// * limit - 可选  1-100
+    /// * offset - 可选  翻页
+    ///
+    /// return : Vec\<RecentActivity\>, 没有动态时返回 [`Error::Null`]
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::entity_v2::Event;
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// for activity in api_v2.get_user_recent_activity(2, Some(20), None).await? {
+    ///     if let Event::Rank { rank, beatmap, .. } = activity.event {
+    ///         println!("{} #{} {}", activity.created_at, rank, beatmap.title);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_user_recent_activity(&self, user_id:i64, limit:Option<i8>, offset:Option<i64>) -> Result<Vec<RecentActivity>> {
+        let mut req = GetUserRecentActivity::new(user_id);
+        req.limit = limit;
+        req.offset = offset;
+        self.request(req).await
+    }
+    /// 按 offset 翻页, 直到返回的数量不足一页 (或者 [`Error::Null`])
+    fn crawl_pages<R, T>(&self, page:impl Fn(i64) -> R + 'static) -> impl Stream<Item = Result<T>> + '_
+        where R:Request<Output = Vec<T>> + 'static, T:'static {
+        let state = Pages { offset: 0, buffer: VecDeque::new(), done: false };
+        stream::try_unfold(state, move |mut state| {
+            let req = (!state.done && state.buffer.is_empty()).then(|| page(state.offset));
+            async move {
+                if let Some(req) = req {
+                    let page = match self.request(req).await {
+                        Ok(page) => page,
+                        Err(Error::Null) => Vec::new(),
+                        Err(err) => return Err(err),
+                    };
+                    state.done = page.len() < PAGE_SIZE as usize;
+                    state.offset += page.len() as i64;
+                    state.buffer.extend(page);
+                }
+                Ok(state.buffer.pop_front().map(|item| (item, state)))
+            }
+        })
+    }
+    /// 发送一个请求, 见 [`request_v2`](crate::request_v2)
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> osu_api_rs::error::Result<()> {
+    /// use osu_api_rs::request_v2::LookupBeatmap;
+    /// # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+    ///
+    /// let beatmap = api_v2.request(LookupBeatmap::new().checksum("c8f08438204abfcdd1a748ebfae67421")).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request<R:Request>(&self, req:R) -> Result<R::Output> {
+        let vec = req.query()?;
+        let url = self.assembly_url(req.path(), vec)?;
+        let access_token = self.access_token().await?;
+        let parse = |data| req.parse(data);
+        match self.http.get(R::ENDPOINT, url.clone(), Some(&access_token), parse).await {
+            // token 被服务器作废 (例: 被撤销, 或者在其他地方刷新过), 刷新后重试一次
+            Err(Error::Unauthorized(_)) => {
+                let access_token = self.refresh_if_stale(&access_token).await?;
+                self.http.get(R::ENDPOINT, url, Some(&access_token), parse).await
+            }
+            result => result,
+        }
+    }
+    fn assembly_url<URL:AsRef<str> + std::fmt::Display>(&self, url: URL, vec:Vec<(&str,String)>) -> Result<Url> {
+        Url::parse_with_params(format!("{}/{}",self.base_url,<URL as Into<URL>>::into(url)).as_str(),
+                               &vec).map_err(|err| Error::InvalidInput(err.to_string()))
     }
 }
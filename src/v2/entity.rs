@@ -2,22 +2,23 @@
 //!
 //! 数据就不详细说明了,自己看看文档吧~
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::Value;
-use crate::entity_v1::Scores;
+use crate::util::{GameMod, Mode, RankStatus};
 
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct Beatmap {
     pub beatmapset_id:i64,
     pub difficulty_rating:f32,
     pub id:i64,
-    pub mode:String,
-    pub status:String,
+    pub mode:Mode,
+    pub status:RankStatus,
     pub total_length:i32,
     pub user_id:i64,
     pub version:String,
-    pub accuracy:i32,
+    pub accuracy:f32,
     pub ar:f32,
-    pub bpm:i64,
+    pub bpm:f32,
     pub convert:bool,
     pub count_circles:i32,
     pub count_sliders:i32,
@@ -26,11 +27,11 @@ pub struct Beatmap {
     pub drain:f32,
     pub hit_length:i32,
     pub is_scoreable:bool,
-    pub last_updated:String,
-    pub mode_int:i32,
+    pub last_updated:DateTime<Utc>,
+    pub mode_int:Mode,
     pub passcount:i32,
     pub playcount:i32,
-    pub ranked:i32,
+    pub ranked:RankStatus,
     pub url:String,
     pub checksum:String,
     pub beatmapset:Beatmapset,
@@ -52,26 +53,86 @@ pub struct Beatmapset{
     pub preview_url:String,
     pub source:String,
     pub spotlight:bool,
-    pub status:String,
+    pub status:RankStatus,
     pub title:String,
     pub title_unicode:String,
     pub user_id:i64,
     pub video:bool,
     pub availability:Availability,
-    pub bpm:i64,
+    pub bpm:f32,
     pub can_be_hyped:bool,
     pub discussion_enabled:bool,
     pub discussion_locked:bool,
     pub is_scoreable:bool,
-    pub last_updated:String,
-    pub legacy_thread_url:String,
+    pub last_updated:DateTime<Utc>,
+    pub legacy_thread_url:Option<String>,
     pub nominations_summary: NominationsSummary,
-    pub ranked: i32,
-    pub ranked_date: String,
+    pub ranked: RankStatus,
+    pub ranked_date: Option<DateTime<Utc>>,
     pub storyboard: bool,
-    pub submitted_date: String,
+    pub submitted_date: Option<DateTime<Utc>>,
     pub tags: String,
+    /// 搜索结果中没有
+    #[serde(default)]
     pub ratings: Vec<i32>,
+    /// 各个难度, 只有搜索结果中有
+    #[serde(default)]
+    pub beatmaps: Vec<ScoreBeatmap>,
+}
+
+/// 谱面集的基本信息, 成绩 / 用户的谱面列表中带着的
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct BeatmapsetCompact {
+    pub artist:String,
+    pub artist_unicode:String,
+    pub covers:Covers,
+    pub creator:String,
+    pub favourite_count:i32,
+    pub id:i64,
+    pub nsfw:bool,
+    pub offset:i32,
+    pub play_count:i32,
+    pub preview_url:String,
+    pub source:String,
+    pub spotlight:bool,
+    pub status:RankStatus,
+    pub title:String,
+    pub title_unicode:String,
+    pub user_id:i64,
+    pub video:bool,
+}
+
+/// 谱面的基本信息
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct BeatmapCompact {
+    pub beatmapset_id:i64,
+    pub difficulty_rating:f32,
+    pub id:i64,
+    pub mode:Mode,
+    pub status:RankStatus,
+    pub total_length:i32,
+    pub user_id:i64,
+    pub version:String,
+}
+
+/// # GET /users/{user}/beatmapsets/most_played
+/// 用户玩过最多次的谱面
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct BeatmapPlaycount {
+    pub beatmap_id:i64,
+    /// 游玩次数
+    pub count:i64,
+    pub beatmap:BeatmapCompact,
+    pub beatmapset:BeatmapsetCompact,
+}
+
+/// 谱面搜索的结果
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct BeatmapsetSearch {
+    pub beatmapsets: Vec<Beatmapset>,
+    /// 下一页的游标, 没有下一页时为 None
+    pub cursor_string: Option<String>,
+    pub total: i64,
 }
 
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
@@ -118,16 +179,18 @@ pub struct UserBeatmapScore {
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct Score {
     pub accuracy:f64,
-    pub best_id:i64,
-    pub created_at:String,
+    /// 失败的成绩 (`passed` 为 false) 没有
+    pub best_id:Option<i64>,
+    pub created_at:DateTime<Utc>,
     pub id:i64,
     pub max_combo:i64,
-    pub mode:String,
-    pub mode_int:i64,
-    pub mods:Vec<String>,
+    pub mode:Mode,
+    pub mode_int:Mode,
+    pub mods:Vec<GameMod>,
     pub passed:bool,
     pub perfect:bool,
-    pub pp:f32,
+    /// 没有 pp 的谱面 (例: loved) 或者失败的成绩为 None
+    pub pp:Option<f32>,
     pub rank:String,
     pub replay:bool,
     pub score:i64,
@@ -136,7 +199,21 @@ pub struct Score {
     pub user_id:i64,
     pub current_user_attributes:Value, // 没获取到,不知道里面什么数据
     pub beatmap:ScoreBeatmap,
+    /// 只有用户的成绩列表中有
+    #[serde(default)]
+    pub beatmapset:Option<BeatmapsetCompact>,
     pub user:User,
+    /// bp 的权重, 只有 best 列表中有
+    #[serde(default)]
+    pub weight:Option<Weight>,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct Weight {
+    /// 例: 95.0
+    pub percentage:f64,
+    /// 加权后的 pp
+    pub pp:f64,
 }
 
 
@@ -146,14 +223,14 @@ pub struct ScoreBeatmap {
     pub beatmapset_id:i64,
     pub difficulty_rating:f32,
     pub id:i64,
-    pub mode:String,
-    pub status:String,
+    pub mode:Mode,
+    pub status:RankStatus,
     pub total_length:i32,
     pub user_id:i64,
     pub version:String,
-    pub accuracy:i32,
+    pub accuracy:f32,
     pub ar:f32,
-    pub bpm:i64,
+    pub bpm:f32,
     pub convert:bool,
     pub count_circles:i32,
     pub count_sliders:i32,
@@ -162,11 +239,11 @@ pub struct ScoreBeatmap {
     pub drain:f32,
     pub hit_length:i32,
     pub is_scoreable:bool,
-    pub last_updated:String,
-    pub mode_int:i32,
+    pub last_updated:DateTime<Utc>,
+    pub mode_int:Mode,
     pub passcount:i32,
     pub playcount:i32,
-    pub ranked:i32,
+    pub ranked:RankStatus,
     pub url:String,
     pub checksum:String,
 }
@@ -191,11 +268,15 @@ pub struct User {
     pub is_deleted:bool,
     pub is_online:bool,
     pub is_supporter:bool,
-    pub last_visit:String,
+    pub last_visit:Option<DateTime<Utc>>,
     pub pm_friends_only:bool,
     pub username:String,
-    pub country:Country,
-    pub cover:Cover,
+    /// 用户成绩列表 (`/users/{user}/scores/{type}`) 中的用户没有
+    #[serde(default)]
+    pub country:Option<Country>,
+    /// 同 `country`
+    #[serde(default)]
+    pub cover:Option<Cover>,
 
 }
 
@@ -208,7 +289,10 @@ pub struct Country {
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct Cover{
     pub url:String,
-    pub id:String,
+    /// 自定义的封面没有 id
+    pub id:Option<String>,
+    #[serde(default)]
+    pub custom_url:Option<String>,
 }
 
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
@@ -219,19 +303,21 @@ pub struct BestBeatmapScores {
 #[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
 pub struct BestScores {
     pub accuracy:f64,
-    pub best_id:i64,
-    pub created_at:String,
+    /// 同 [`Score::best_id`]
+    pub best_id:Option<i64>,
+    pub created_at:DateTime<Utc>,
     pub id:i64,
-    pub max_combo:i32,
-    pub mode:String,
-    pub mode_int:i32,
-    pub mods:Vec<String>,
+    pub max_combo:i64,
+    pub mode:Mode,
+    pub mode_int:Mode,
+    pub mods:Vec<GameMod>,
     pub passed:bool,
     pub perfect:bool,
-    pub pp:f32,
+    /// 同 [`Score::pp`]
+    pub pp:Option<f32>,
     pub rank:String,
     pub replay:bool,
-    pub score:i32,
+    pub score:i64,
     pub statistics:Statistics,
     pub r#type:String,
     pub user_id:i64,
@@ -239,3 +325,318 @@ pub struct BestScores {
     pub user:User,
 }
 
+
+/// # GET /users/{user}/{mode}
+/// 用户的完整信息
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct UserExtended {
+    pub avatar_url:String,
+    pub country_code:String,
+    pub default_group:String,
+    pub id:i64,
+    pub is_active:bool,
+    pub is_bot:bool,
+    pub is_deleted:bool,
+    pub is_online:bool,
+    pub is_supporter:bool,
+    pub last_visit:Option<DateTime<Utc>>,
+    pub pm_friends_only:bool,
+    pub profile_colour:Option<String>,
+    pub username:String,
+    pub cover_url:Option<String>,
+    pub discord:Option<String>,
+    pub has_supported:bool,
+    pub interests:Option<String>,
+    pub join_date:DateTime<Utc>,
+    pub kudosu:Kudosu,
+    pub location:Option<String>,
+    pub max_blocks:i32,
+    pub max_friends:i32,
+    pub occupation:Option<String>,
+    /// 用户设置的默认模式
+    pub playmode:Mode,
+    pub playstyle:Option<Vec<String>>,
+    pub post_count:i64,
+    pub profile_order:Vec<String>,
+    pub title:Option<String>,
+    pub title_url:Option<String>,
+    pub twitter:Option<String>,
+    pub website:Option<String>,
+    pub country:Country,
+    pub cover:Cover,
+    #[serde(default)]
+    pub badges:Vec<Badge>,
+    #[serde(default)]
+    pub beatmap_playcounts_count:i64,
+    #[serde(default)]
+    pub favourite_beatmapset_count:i64,
+    #[serde(default)]
+    pub follower_count:i64,
+    #[serde(default)]
+    pub graveyard_beatmapset_count:i64,
+    #[serde(default)]
+    pub guest_beatmapset_count:i64,
+    #[serde(default)]
+    pub loved_beatmapset_count:i64,
+    #[serde(default)]
+    pub mapping_follower_count:i64,
+    #[serde(default)]
+    pub nominated_beatmapset_count:i64,
+    #[serde(default)]
+    pub pending_beatmapset_count:i64,
+    #[serde(default)]
+    pub ranked_beatmapset_count:i64,
+    #[serde(default)]
+    pub scores_best_count:i64,
+    #[serde(default)]
+    pub scores_first_count:i64,
+    #[serde(default)]
+    pub scores_pinned_count:i64,
+    #[serde(default)]
+    pub scores_recent_count:i64,
+    #[serde(default)]
+    pub groups:Vec<UserGroup>,
+    /// 每月的游玩次数
+    #[serde(default)]
+    pub monthly_playcounts:Vec<MonthlyCount>,
+    /// 每月回放被观看的次数
+    #[serde(default)]
+    pub replays_watched_counts:Vec<MonthlyCount>,
+    pub page:Option<UserPage>,
+    #[serde(default)]
+    pub previous_usernames:Vec<String>,
+    /// 最高排名, 没有排名时为 None
+    pub rank_highest:Option<RankHighest>,
+    /// 最近 90 天的排名, 没有排名时为 None
+    pub rank_history:Option<RankHistory>,
+    /// 请求的模式 (没有指定时为 `playmode`) 的统计数据
+    pub statistics:UserStatistics,
+    #[serde(default)]
+    pub support_level:i32,
+    #[serde(default)]
+    pub user_achievements:Vec<UserAchievement>,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct Kudosu {
+    pub total:i64,
+    pub available:i64,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct Badge {
+    pub awarded_at:DateTime<Utc>,
+    pub description:String,
+    pub image_url:String,
+    #[serde(rename = "image@2x_url", default)]
+    pub image_2x_url:Option<String>,
+    pub url:String,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct UserGroup {
+    pub id:i64,
+    pub identifier:String,
+    pub name:String,
+    pub short_name:String,
+    pub colour:Option<String>,
+    #[serde(default)]
+    pub description:Option<String>,
+    pub has_listing:bool,
+    pub has_playmodes:bool,
+    pub is_probationary:bool,
+    /// 只在 `has_playmodes` 时有
+    #[serde(default)]
+    pub playmodes:Option<Vec<Mode>>,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct MonthlyCount {
+    /// 每月的第一天
+    pub start_date:NaiveDate,
+    pub count:i64,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct UserPage {
+    pub html:String,
+    /// BBCode
+    pub raw:String,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct RankHighest {
+    pub rank:i64,
+    pub updated_at:DateTime<Utc>,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct RankHistory {
+    pub mode:Mode,
+    /// 每天的排名, 最后一个是今天
+    pub data:Vec<i64>,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct UserAchievement {
+    pub achieved_at:DateTime<Utc>,
+    pub achievement_id:i64,
+}
+
+/// 用户在某个模式的统计数据
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct UserStatistics {
+    pub count_100:i64,
+    pub count_300:i64,
+    pub count_50:i64,
+    pub count_miss:i64,
+    pub level:Level,
+    /// 没有排名 (例: 不活跃) 时为 None
+    pub global_rank:Option<i64>,
+    #[serde(default)]
+    pub country_rank:Option<i64>,
+    pub pp:f64,
+    pub ranked_score:i64,
+    pub hit_accuracy:f64,
+    pub play_count:i64,
+    /// 秒
+    pub play_time:Option<i64>,
+    pub total_score:i64,
+    pub total_hits:i64,
+    pub maximum_combo:i64,
+    pub replays_watched_by_others:i64,
+    pub is_ranked:bool,
+    pub grade_counts:GradeCounts,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct Level {
+    pub current:i32,
+    /// 到下一级的进度, 0-100
+    pub progress:i32,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct GradeCounts {
+    pub ss:i64,
+    pub ssh:i64,
+    pub s:i64,
+    pub sh:i64,
+    pub a:i64,
+}
+
+/// # GET /users/{user}/recent_activity
+/// 用户的一条动态
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct RecentActivity {
+    pub id:i64,
+    pub created_at:DateTime<Utc>,
+    #[serde(flatten)]
+    pub event:Event,
+}
+
+/// 动态的内容, 按 `type` 区分
+///
+/// 引用: https://osu.ppy.sh/docs/index.html#event
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Event {
+    /// 获得成就
+    Achievement {
+        achievement:EventAchievement,
+        user:EventUser,
+    },
+    /// 谱面的游玩次数达到某个数
+    BeatmapPlaycount {
+        beatmap:EventBeatmap,
+        count:i64,
+    },
+    /// 谱面 ranked / approved / qualified / loved
+    BeatmapsetApprove {
+        approval:RankStatus,
+        beatmapset:EventBeatmap,
+        user:EventUser,
+    },
+    BeatmapsetDelete {
+        beatmapset:EventBeatmap,
+    },
+    /// 谱面从 graveyard 中复活
+    BeatmapsetRevive {
+        beatmapset:EventBeatmap,
+        user:EventUser,
+    },
+    BeatmapsetUpdate {
+        beatmapset:EventBeatmap,
+        user:EventUser,
+    },
+    BeatmapsetUpload {
+        beatmapset:EventBeatmap,
+        user:EventUser,
+    },
+    /// 在谱面上取得排名 (前 50)
+    Rank {
+        /// 成绩的评级, 例: `S`, `XH`
+        #[serde(rename = "scoreRank")]
+        score_rank:String,
+        rank:i64,
+        mode:Mode,
+        beatmap:EventBeatmap,
+        user:EventUser,
+    },
+    /// 失去谱面的第一名
+    RankLost {
+        mode:Mode,
+        beatmap:EventBeatmap,
+        user:EventUser,
+    },
+    UserSupportAgain {
+        user:EventUser,
+    },
+    UserSupportFirst {
+        user:EventUser,
+    },
+    /// 收到别人赠送的 supporter
+    UserSupportGift {
+        user:EventUser,
+    },
+    /// 改名, 旧的名字在 [`EventUser::previous_username`]
+    UsernameChange {
+        user:EventUser,
+    },
+    /// 新增的, 还不认识的类型
+    #[serde(other)]
+    Unknown,
+}
+
+/// 动态中的谱面 / 谱面集
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct EventBeatmap {
+    pub title:String,
+    /// 相对路径, 例: `/b/252002?m=0`
+    pub url:String,
+}
+
+/// 动态中的用户
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct EventUser {
+    pub username:String,
+    /// 相对路径, 例: `/u/2`
+    pub url:String,
+    /// 只有 `usernameChange` 有
+    #[serde(default, rename = "previousUsername")]
+    pub previous_username:Option<String>,
+}
+
+#[derive(Debug, Clone,serde::Serialize,serde::Deserialize)]
+pub struct EventAchievement {
+    pub id:i64,
+    pub name:String,
+    pub slug:String,
+    pub description:String,
+    pub grouping:String,
+    pub icon_url:String,
+    pub ordering:i32,
+    pub instructions:Option<String>,
+    /// 和模式无关的成就为 None
+    pub mode:Option<Mode>,
+}
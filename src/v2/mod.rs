@@ -1,3 +1,5 @@
 pub mod api;
 pub mod entity;
+pub mod request;
 
+pub mod oauth;
@@ -0,0 +1,434 @@
+//! # OAuth 授权码模式
+//! 客户端凭据 (`client_credentials`) 只能使用 `public` 权限, 需要用户权限的接口 (`/me`, 好友, 聊天, 论坛等)
+//! 需要用户在浏览器中授权:
+//!
+//! 1. 用 [`ApiV2Builder::authorization_url`](crate::ApiV2Builder::authorization_url) 生成授权链接, 让用户打开
+//! 2. 用户同意后浏览器会跳转到 `redirect_uri?code=...&state=...`
+//! 3. 用 [`ApiV2Builder::build_with_code`](crate::ApiV2Builder::build_with_code) 换取 access token 和 refresh token
+//!
+//! 命令行工具可以把 `redirect_uri` 设置为 `http://127.0.0.1:端口`, 用 [`wait_for_code`] 接收跳转:
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> osu_api_rs::error::Result<()> {
+//! use osu_api_rs::ApiV2;
+//! use osu_api_rs::oauth_v2::{self, Scope};
+//! # let (client_id, client_secret) = ("1", "secret");
+//!
+//! let builder = ApiV2::builder(client_id, client_secret)
+//!     .redirect_uri("http://127.0.0.1:7270")
+//!     .scopes([Scope::Identify, Scope::Public, Scope::FriendsRead]);
+//! let state = oauth_v2::new_state();
+//! println!("请打开: {}", builder.authorization_url(&state)?);
+//! let code = oauth_v2::wait_for_code("127.0.0.1:7270", &state).await?;
+//! let api_v2 = builder.build_with_code(&code).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! osu官方文档说明: https://osu.ppy.sh/docs/index.html#authorization-code-grant
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use crate::error::{Error, Result};
+use crate::http::Http;
+
+/// 用户授权页面
+pub static OSU_API_2_AUTHORIZE: &str = "https://osu.ppy.sh/oauth/authorize";
+
+/// 权限范围
+///
+/// 引用: https://osu.ppy.sh/docs/index.html#scopes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// 代表用户读取公开的数据, 客户端凭据只能使用这个
+    Public,
+    /// 读取用户自己的信息 (`/me`), 授权码模式总是会有这个
+    Identify,
+    /// 读取好友列表
+    FriendsRead,
+    /// 以用户的身份发帖
+    ForumWrite,
+    /// 读取聊天消息
+    ChatRead,
+    /// 以用户的身份发送聊天消息
+    ChatWrite,
+    /// 加入 / 离开聊天频道
+    ChatWriteManage,
+    /// 以应用所有者的身份操作 (只能用于客户端凭据, 仅限机器人账号)
+    Delegate,
+}
+
+impl Scope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Public => "public",
+            Scope::Identify => "identify",
+            Scope::FriendsRead => "friends.read",
+            Scope::ForumWrite => "forum.write",
+            Scope::ChatRead => "chat.read",
+            Scope::ChatWrite => "chat.write",
+            Scope::ChatWriteManage => "chat.write_manage",
+            Scope::Delegate => "delegate",
+        }
+    }
+    /// 用空格连接, 用于请求参数
+    pub(crate) fn join(scopes: &[Scope]) -> String {
+        scopes.iter().map(Scope::name).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl FromStr for Scope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "public" => Ok(Scope::Public),
+            "identify" => Ok(Scope::Identify),
+            "friends.read" => Ok(Scope::FriendsRead),
+            "forum.write" => Ok(Scope::ForumWrite),
+            "chat.read" => Ok(Scope::ChatRead),
+            "chat.write" => Ok(Scope::ChatWrite),
+            "chat.write_manage" => Ok(Scope::ChatWriteManage),
+            "delegate" => Ok(Scope::Delegate),
+            other => Err(Error::InvalidInput(format!("未知的 scope: {}", other))),
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for Scope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Scope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// token 到期前多久开始刷新
+pub const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// # Token
+/// osu! 返回的 token, 有效期一般为 1 天
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Token {
+    /// 认证类型: Bearer
+    pub token_type: String,
+    pub access_token: String,
+    /// 授权码模式才有, 用来刷新 token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// 有效时间 (秒)
+    pub expires_in: i64,
+    /// 获取 token 的时间, osu! 返回的数据中没有, 解析时为当前时间
+    #[serde(default = "Utc::now")]
+    pub issued_at: DateTime<Utc>,
+    /// 申请的权限, osu! 返回的数据中没有, 为获取 token 时申请的权限
+    #[serde(default)]
+    pub scopes: Vec<Scope>,
+}
+
+impl Token {
+    /// 到期时间
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.issued_at + chrono::Duration::seconds(self.expires_in)
+    }
+    /// 是否已经过期
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(Duration::ZERO)
+    }
+    /// 是否会在 `margin` 之内过期
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        let margin = chrono::Duration::from_std(margin).unwrap_or(chrono::Duration::zero());
+        Utc::now() + margin >= self.expires_at()
+    }
+}
+
+/// 不输出 token 本身
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("token_type", &self.token_type)
+            .field("access_token", &"***")
+            .field("refresh_token", &self.refresh_token.as_ref().map(|_| "***"))
+            .field("expires_in", &self.expires_in)
+            .field("issued_at", &self.issued_at)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+/// 获取 / 刷新 token 需要的信息
+#[derive(Clone)]
+pub(crate) struct Credentials {
+    pub(crate) client_id: String,
+    pub(crate) client_secret: String,
+    /// 获取 token 的url
+    pub(crate) token_url: String,
+    pub(crate) scopes: Vec<Scope>,
+}
+
+impl Credentials {
+    /// 客户端凭据
+    pub(crate) async fn client_credentials(&self, http: &Http) -> Result<Token> {
+        self.grant(http, json!({
+            "grant_type": "client_credentials",
+            "scope": Scope::join(&self.scopes),
+        })).await.map_err(|err| auth_failed(err, "无法获取 token, 请检查 client_id / client_secret"))
+    }
+    /// 授权码换取 token
+    pub(crate) async fn authorization_code(&self, http: &Http, code: &str, redirect_uri: &str) -> Result<Token> {
+        self.grant(http, json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "redirect_uri": redirect_uri,
+        })).await.map_err(|err| auth_failed(err, "授权码无效或者已经使用过"))
+    }
+    /// 用 refresh token 刷新, 新的 token 中没有 refresh token 时沿用旧的
+    pub(crate) async fn refresh(&self, http: &Http, refresh_token: &str) -> Result<Token> {
+        let mut token = self.grant(http, json!({
+            "grant_type": "refresh_token",
+            "refresh_token": refresh_token,
+            "scope": Scope::join(&self.scopes),
+        })).await.map_err(|err| auth_failed(err, "refresh token 已失效, 需要重新授权"))?;
+        if token.refresh_token.is_none() {
+            token.refresh_token = Some(refresh_token.to_string());
+        }
+        Ok(token)
+    }
+    async fn grant(&self, http: &Http, mut json: Value) -> Result<Token> {
+        json["client_id"] = json!(self.client_id);
+        json["client_secret"] = json!(self.client_secret);
+        let data = http.post(&self.token_url, &json, None).await?;
+        let mut token = serde_json::from_str::<Token>(&data).map_err(|err| Error::Auth(format!("无法解析 token: {}", err)))?;
+        if token.scopes.is_empty() {
+            token.scopes = self.scopes.clone();
+        }
+        Ok(token)
+    }
+}
+
+/// 获取 token 时服务器拒绝 (4xx, 例: 400 `{"error": "invalid_grant"}`) -> [`Error::Auth`]
+fn auth_failed(err: Error, reason: &str) -> Error {
+    match err {
+        Error::Unauthorized(msg) | Error::Auth(msg) | Error::Api(msg) => Error::Auth(format!("{}: {}", reason, msg)),
+        Error::Status { status: 400..=499, body } => Error::Auth(format!("{}: {}", reason, body)),
+        err => err,
+    }
+}
+
+/// 不输出 client_secret
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"***")
+            .field("token_url", &self.token_url)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+/// # TokenStore
+/// token 的保存方式, 程序重启后可以继续使用之前的 token (以及 refresh token), 不需要重新授权。
+///
+/// 默认提供 [`MemoryTokenStore`] 和 [`FileTokenStore`], 需要存到数据库的话自己实现这个 trait。
+/// 获取 / 刷新 token 后会调用 [`save`](TokenStore::save)。
+pub trait TokenStore: fmt::Debug + Send + Sync {
+    /// 读取保存的 token, 没有时返回 None
+    fn load(&self) -> Result<Option<Token>>;
+    /// 保存 token
+    fn save(&self, token: &Token) -> Result<()>;
+}
+
+/// # MemoryTokenStore
+/// 保存在内存中, 主要用于测试
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    token: Mutex<Option<Token>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> MemoryTokenStore {
+        MemoryTokenStore::default()
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self) -> Result<Option<Token>> {
+        Ok(self.token.lock().unwrap_or_else(|err| err.into_inner()).clone())
+    }
+    fn save(&self, token: &Token) -> Result<()> {
+        *self.token.lock().unwrap_or_else(|err| err.into_inner()) = Some(token.clone());
+        Ok(())
+    }
+}
+
+/// # FileTokenStore
+/// 以 JSON 保存在文件中, 先写入临时文件再替换, 不会因为写到一半退出而损坏。
+///
+/// 文件中是明文的 token, unix 下文件权限为 `600`
+#[derive(Debug, Clone)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    pub fn new(path: impl Into<PathBuf>) -> FileTokenStore {
+        FileTokenStore { path: path.into() }
+    }
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<Token>> {
+        match fs::read_to_string(&self.path) {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+    fn save(&self, token: &Token) -> Result<()> {
+        let data = serde_json::to_vec_pretty(token)?;
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp)?;
+        file.write_all(&data)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// 生成一个随机的 `state`, 用来防止 CSRF, 回调时需要检查是否一致
+pub fn new_state() -> String {
+    let random = || {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos());
+        hasher.finish()
+    };
+    format!("{:016x}{:016x}", random(), random())
+}
+
+/// 授权链接
+pub(crate) fn authorization_url(authorize_url: &str, client_id: &str, redirect_uri: &str, scopes: &[Scope], state: &str) -> Result<Url> {
+    Url::parse_with_params(authorize_url, &[
+        ("client_id", client_id),
+        ("redirect_uri", redirect_uri),
+        ("response_type", "code"),
+        ("scope", &Scope::join(scopes)),
+        ("state", state),
+    ]).map_err(|err| Error::InvalidInput(err.to_string()))
+}
+
+/// 回调连接读取请求头的超时时间, 超时的连接 (例: 浏览器预连接) 直接关闭
+const CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// # 接收授权回调
+/// 在 `addr` 上监听, 等待浏览器跳转到 `redirect_uri`, 返回其中的 `code`。
+///
+/// * `state` 不一致的请求会被忽略 (返回 400), 防止其他页面伪造回调
+/// * 用户拒绝授权时 (`?error=access_denied&state=...`) 返回 [`Error::Auth`]
+/// * 没有 `code` 的请求 (例: `/favicon.ico`) 会被忽略
+/// * 每个连接单独处理, 不发送请求的连接不会挡住后面的回调
+///
+/// 只处理简单的 GET 请求, 仅用于命令行工具, 不要暴露到公网。
+pub async fn wait_for_code(addr: impl ToSocketAddrs, state: &str) -> Result<String> {
+    let listener = TcpListener::bind(addr).await.map_err(|err| Error::Auth(format!("无法监听回调地址: {}", err)))?;
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted.map_err(|err| Error::Auth(err.to_string()))?;
+                let sender = sender.clone();
+                let state = state.to_string();
+                tokio::spawn(async move {
+                    if let Some(result) = handle_callback(stream, &state).await {
+                        let _ = sender.send(result).await;
+                    }
+                });
+            }
+            Some(result) = receiver.recv() => return result,
+        }
+    }
+}
+
+/// 处理一个回调连接, 不是授权结果的请求返回 None
+async fn handle_callback(mut stream: TcpStream, state: &str) -> Option<Result<String>> {
+    let head = tokio::time::timeout(CALLBACK_READ_TIMEOUT, read_head(&mut stream)).await.ok()?;
+    let head = String::from_utf8_lossy(&head);
+    // 请求行: GET /?code=...&state=... HTTP/1.1
+    let mut request_line = head.lines().next()?.split_whitespace();
+    let (method, path, version) = (request_line.next()?, request_line.next()?, request_line.next()?);
+    if method != "GET" || !version.starts_with("HTTP/") {
+        return None;
+    }
+    let url = Url::parse(&format!("http://localhost{}", path)).ok()?;
+    let query = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+    let result = match (query("state").as_deref() == Some(state), query("code"), query("error")) {
+        (_, None, None) => None,
+        (false, _, _) => {
+            let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            return None;
+        }
+        (true, _, Some(error)) => Some(Err(Error::Auth(format!("用户拒绝了授权: {}", error)))),
+        (true, Some(code), None) => Some(Ok(code)),
+    };
+    let body = match &result {
+        Some(Ok(_)) => "授权成功, 可以关闭这个页面了。",
+        Some(Err(_)) => "授权失败, 请回到程序查看原因。",
+        None => {
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            return None;
+        }
+    };
+    let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+    let _ = stream.write_all(response.as_bytes()).await;
+    result
+}
+
+/// 读到请求头结束 (或者连接关闭 / 超过 16K) 为止
+async fn read_head(stream: &mut TcpStream) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") && buffer.len() < 16 * 1024 {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    }
+    buffer
+}
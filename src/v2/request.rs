@@ -0,0 +1,700 @@
+//! # 请求构建
+//! 每个接口对应一个请求结构体, 用链式调用设置参数, 发送前会检查参数范围。
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> osu_api_rs::error::Result<()> {
+//! use osu_api_rs::Mode;
+//! use osu_api_rs::request_v2::GetBeatmapScores;
+//! # let api_v2 = osu_api_rs::ApiV2::new("1", "secret").await?;
+//!
+//! let scores = api_v2.request(GetBeatmapScores::new(252002).mode(Mode::Osu).limit(50)).await;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::entity_v2::{Beatmap, BeatmapPlaycount, Beatmapset, BeatmapsetSearch, BestBeatmapScores, RecentActivity, Score, User, UserBeatmapScore, UserExtended};
+use crate::error::{Error, Result};
+use crate::util::{assembly_data, data_serialize, data_serialize_vec, DataType, Genre, Language, Mode, Mods, RankStatus, UserType};
+use crate::v1::request::{check_limit, check_mods};
+
+/// v2 的请求
+pub trait Request {
+    /// 返回的数据
+    type Output;
+    /// 接口名, 路径中的参数用 `{}` 表示, 例: `beatmaps/{beatmap}/scores`
+    const ENDPOINT: &'static str;
+    /// 实际的路径, 例: `beatmaps/252002/scores`
+    fn path(&self) -> String;
+    /// 检查参数, 组装查询参数
+    fn query(&self) -> Result<Vec<(&'static str, String)>>;
+    /// 解析返回的数据
+    fn parse(&self, data: String) -> Result<Self::Output>;
+}
+
+/// # GET /beatmaps/lookup
+/// 见 [`ApiV2::lookup_beatmap_all`](crate::ApiV2::lookup_beatmap_all), 至少需要一个参数
+#[derive(Debug, Clone, Default)]
+pub struct LookupBeatmap<'a> {
+    pub(crate) checksum: Option<&'a str>,
+    pub(crate) filename: Option<&'a str>,
+    pub(crate) beatmap_id: Option<i64>,
+}
+
+impl<'a> LookupBeatmap<'a> {
+    pub fn new() -> LookupBeatmap<'a> {
+        LookupBeatmap::default()
+    }
+    /// 谱面文件的 md5
+    pub fn checksum(mut self, checksum: &'a str) -> LookupBeatmap<'a> {
+        self.checksum = Some(checksum);
+        self
+    }
+    /// 谱面的文件名
+    pub fn filename(mut self, filename: &'a str) -> LookupBeatmap<'a> {
+        self.filename = Some(filename);
+        self
+    }
+    pub fn id(mut self, beatmap_id: i64) -> LookupBeatmap<'a> {
+        self.beatmap_id = Some(beatmap_id);
+        self
+    }
+}
+
+impl Request for LookupBeatmap<'_> {
+    type Output = Beatmap;
+    const ENDPOINT: &'static str = "beatmaps/lookup";
+
+    fn path(&self) -> String {
+        Self::ENDPOINT.to_string()
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        if self.checksum.is_none() && self.filename.is_none() && self.beatmap_id.is_none() {
+            return Err(Error::InvalidInput("lookup 至少需要 checksum / filename / id 其中一个".to_string()));
+        }
+        let mut vec = vec![];
+        assembly_data(&[
+            ("id", DataType::Int64(self.beatmap_id)),
+            ("checksum", DataType::String(self.checksum)),
+            ("filename", DataType::String(self.filename)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
+
+/// # GET /beatmaps/{beatmap}/scores/users/{user}
+/// 见 [`ApiV2::get_user_beatmap_score_complete`](crate::ApiV2::get_user_beatmap_score_complete)
+#[derive(Debug, Clone)]
+pub struct GetUserBeatmapScore {
+    pub(crate) beatmap_id: i64,
+    pub(crate) user_id: i64,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) mods: Option<Mods>,
+}
+
+impl GetUserBeatmapScore {
+    pub fn new(beatmap_id: i64, user_id: i64) -> GetUserBeatmapScore {
+        GetUserBeatmapScore { beatmap_id, user_id, mode: None, mods: None }
+    }
+    pub fn mode(mut self, mode: Mode) -> GetUserBeatmapScore {
+        self.mode = Some(mode);
+        self
+    }
+    pub fn mods(mut self, mods: Mods) -> GetUserBeatmapScore {
+        self.mods = Some(mods);
+        self
+    }
+}
+
+impl Request for GetUserBeatmapScore {
+    type Output = UserBeatmapScore;
+    const ENDPOINT: &'static str = "beatmaps/{beatmap}/scores/users/{user}";
+
+    fn path(&self) -> String {
+        format!("beatmaps/{}/scores/users/{}", self.beatmap_id, self.user_id)
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_mods(self.mods, self.mode)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("mode", DataType::Mode(self.mode, true)),
+            ("mods[]", DataType::Mods(self.mods, true)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
+
+/// # GET /beatmaps/{beatmap}/scores/users/{user}/all
+/// 见 [`ApiV2::get_user_beatmap_scores_complete_all`](crate::ApiV2::get_user_beatmap_scores_complete_all)
+#[derive(Debug, Clone)]
+pub struct GetUserBeatmapScores {
+    pub(crate) beatmap_id: i64,
+    pub(crate) user_id: i64,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) mods: Option<Mods>,
+}
+
+impl GetUserBeatmapScores {
+    pub fn new(beatmap_id: i64, user_id: i64) -> GetUserBeatmapScores {
+        GetUserBeatmapScores { beatmap_id, user_id, mode: None, mods: None }
+    }
+    pub fn mode(mut self, mode: Mode) -> GetUserBeatmapScores {
+        self.mode = Some(mode);
+        self
+    }
+    pub fn mods(mut self, mods: Mods) -> GetUserBeatmapScores {
+        self.mods = Some(mods);
+        self
+    }
+}
+
+impl Request for GetUserBeatmapScores {
+    type Output = Vec<UserBeatmapScore>;
+    const ENDPOINT: &'static str = "beatmaps/{beatmap}/scores/users/{user}/all";
+
+    fn path(&self) -> String {
+        format!("beatmaps/{}/scores/users/{}/all", self.beatmap_id, self.user_id)
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_mods(self.mods, self.mode)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("mode", DataType::Mode(self.mode, true)),
+            ("mods[]", DataType::Mods(self.mods, true)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// # GET /beatmaps/{beatmap}/scores
+/// 见 [`ApiV2::get_beatmap_score`](crate::ApiV2::get_beatmap_score)
+#[derive(Debug, Clone)]
+pub struct GetBeatmapScores<'a> {
+    pub(crate) beatmap_id: i64,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) mods: Option<Mods>,
+    pub(crate) ranking_type: Option<&'a str>,
+    pub(crate) limit: Option<i8>,
+}
+
+impl<'a> GetBeatmapScores<'a> {
+    pub fn new(beatmap_id: i64) -> GetBeatmapScores<'a> {
+        GetBeatmapScores { beatmap_id, mode: None, mods: None, ranking_type: None, limit: None }
+    }
+    pub fn mode(mut self, mode: Mode) -> GetBeatmapScores<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    pub fn mods(mut self, mods: Mods) -> GetBeatmapScores<'a> {
+        self.mods = Some(mods);
+        self
+    }
+    /// 排行榜类型 (global, country, friend), 后两个需要用户授权
+    pub fn ranking_type(mut self, ranking_type: &'a str) -> GetBeatmapScores<'a> {
+        self.ranking_type = Some(ranking_type);
+        self
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetBeatmapScores<'a> {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Request for GetBeatmapScores<'_> {
+    type Output = BestBeatmapScores;
+    const ENDPOINT: &'static str = "beatmaps/{beatmap}/scores";
+
+    fn path(&self) -> String {
+        format!("beatmaps/{}/scores", self.beatmap_id)
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_limit("limit", self.limit.map(i64::from), 100)?;
+        check_mods(self.mods, self.mode)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("mode", DataType::Mode(self.mode, true)),
+            ("mods[]", DataType::Mods(self.mods, true)),
+            ("type", DataType::String(self.ranking_type)),
+            ("limit", DataType::Int8(self.limit)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
+
+/// # GET /beatmapsets/search
+/// 见 [`ApiV2::search_beatmapsets`](crate::ApiV2::search_beatmapsets)
+#[derive(Debug, Clone, Default)]
+pub struct SearchBeatmapsets<'a> {
+    pub(crate) query: Option<&'a str>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) status: Option<RankStatus>,
+    pub(crate) genre: Option<Genre>,
+    pub(crate) language: Option<Language>,
+    pub(crate) nsfw: Option<bool>,
+    pub(crate) sort: Option<&'a str>,
+    pub(crate) cursor: Option<&'a str>,
+}
+
+impl<'a> SearchBeatmapsets<'a> {
+    pub fn new() -> SearchBeatmapsets<'a> {
+        SearchBeatmapsets::default()
+    }
+    /// 搜索关键字
+    pub fn query(mut self, query: &'a str) -> SearchBeatmapsets<'a> {
+        self.query = Some(query);
+        self
+    }
+    pub fn mode(mut self, mode: Mode) -> SearchBeatmapsets<'a> {
+        self.mode = Some(mode);
+        self
+    }
+    /// 谱面状态, 默认只返回有排行榜的谱面, `Ranked` 和 `Approved` 都会返回 ranked + approved
+    pub fn status(mut self, status: RankStatus) -> SearchBeatmapsets<'a> {
+        self.status = Some(status);
+        self
+    }
+    pub fn genre(mut self, genre: Genre) -> SearchBeatmapsets<'a> {
+        self.genre = Some(genre);
+        self
+    }
+    pub fn language(mut self, language: Language) -> SearchBeatmapsets<'a> {
+        self.language = Some(language);
+        self
+    }
+    /// 是否包括 nsfw 的谱面
+    pub fn nsfw(mut self, nsfw: bool) -> SearchBeatmapsets<'a> {
+        self.nsfw = Some(nsfw);
+        self
+    }
+    /// 排序, 例: `ranked_desc`, `plays_desc`
+    pub fn sort(mut self, sort: &'a str) -> SearchBeatmapsets<'a> {
+        self.sort = Some(sort);
+        self
+    }
+    /// 上一页返回的 [`cursor_string`](BeatmapsetSearch::cursor_string)
+    pub fn cursor(mut self, cursor: &'a str) -> SearchBeatmapsets<'a> {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+impl Request for SearchBeatmapsets<'_> {
+    type Output = BeatmapsetSearch;
+    const ENDPOINT: &'static str = "beatmapsets/search";
+
+    fn path(&self) -> String {
+        Self::ENDPOINT.to_string()
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        // 搜索接口的 s 参数没有 approved, ranked 中已经包括了
+        let status = self.status.map(|status| match status {
+            RankStatus::Approved => RankStatus::Ranked.name(),
+            status => status.name(),
+        });
+        let mut vec = vec![];
+        assembly_data(&[
+            ("q", DataType::String(self.query)),
+            ("m", DataType::Mode(self.mode, false)),
+            ("s", DataType::String(status)),
+            ("g", DataType::Int8(self.genre.map(|genre| genre.id()))),
+            ("l", DataType::Int8(self.language.map(|language| language.id()))),
+            ("sort", DataType::String(self.sort)),
+            ("cursor_string", DataType::String(self.cursor)),
+        ], &mut vec);
+        if let Some(nsfw) = self.nsfw {
+            vec.push(("nsfw", nsfw.to_string()));
+        }
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
+
+/// 路径中的用户, 用户名加上 `@` 前缀, 避免纯数字的用户名被当成 id
+pub(crate) fn user_path(user: UserType<'_>) -> String {
+    match user {
+        UserType::USERID(id) => id.to_string(),
+        UserType::USERNAME(name) => format!("@{}", name),
+    }
+}
+
+/// # GET /users/{user}/{mode}
+/// 见 [`ApiV2::get_user`](crate::ApiV2::get_user)
+#[derive(Debug, Clone)]
+pub struct GetUser<'a> {
+    pub(crate) user: UserType<'a>,
+    pub(crate) mode: Option<Mode>,
+}
+
+impl<'a> GetUser<'a> {
+    pub fn new(user: UserType<'a>) -> GetUser<'a> {
+        GetUser { user, mode: None }
+    }
+    /// 统计数据的模式, 默认为用户设置的模式
+    pub fn mode(mut self, mode: Mode) -> GetUser<'a> {
+        self.mode = Some(mode);
+        self
+    }
+}
+
+impl Request for GetUser<'_> {
+    type Output = UserExtended;
+    const ENDPOINT: &'static str = "users/{user}/{mode}";
+
+    fn path(&self) -> String {
+        match self.mode {
+            Some(mode) => format!("users/{}/{}", user_path(self.user), mode.name()),
+            None => format!("users/{}", user_path(self.user)),
+        }
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        if let UserType::USERNAME(name) = self.user {
+            if name.trim().is_empty() {
+                return Err(Error::InvalidInput("用户名不能为空".to_string()));
+            }
+        }
+        Ok(vec![])
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize(data)
+    }
+}
+
+/// `GET /users` 一次最多查询的用户数量
+pub const USERS_PER_REQUEST: usize = 50;
+
+/// # GET /users
+/// 一次最多 [`USERS_PER_REQUEST`] 个, 更多的见 [`ApiV2::get_users`](crate::ApiV2::get_users)
+///
+/// 返回的顺序不一定和 ids 一致, 不存在的用户不会返回
+#[derive(Debug, Clone, Default)]
+pub struct GetUsers {
+    pub(crate) ids: Vec<i64>,
+}
+
+impl GetUsers {
+    pub fn new(ids: impl IntoIterator<Item = i64>) -> GetUsers {
+        GetUsers { ids: ids.into_iter().collect() }
+    }
+}
+
+impl Request for GetUsers {
+    type Output = Vec<User>;
+    const ENDPOINT: &'static str = "users";
+
+    fn path(&self) -> String {
+        Self::ENDPOINT.to_string()
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        if self.ids.is_empty() || self.ids.len() > USERS_PER_REQUEST {
+            return Err(Error::InvalidInput(format!("ids 的数量必须在 1-{} 之间, 当前: {}", USERS_PER_REQUEST, self.ids.len())));
+        }
+        Ok(self.ids.iter().map(|id| ("ids[]", id.to_string())).collect())
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Users {
+            users: Vec<User>,
+        }
+        data_serialize::<Users>(data).map(|data| data.users)
+    }
+}
+
+/// 用户成绩列表的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserScoreType {
+    /// bp, 最多 200 个 (offset + limit <= 200)
+    Best,
+    /// 第一名的成绩
+    Firsts,
+    /// 最近 24 小时的成绩
+    Recent,
+    /// 置顶的成绩
+    Pinned,
+}
+
+impl UserScoreType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserScoreType::Best => "best",
+            UserScoreType::Firsts => "firsts",
+            UserScoreType::Recent => "recent",
+            UserScoreType::Pinned => "pinned",
+        }
+    }
+}
+
+/// # GET /users/{user}/scores/{type}
+/// 见 [`ApiV2::get_user_scores`](crate::ApiV2::get_user_scores)
+#[derive(Debug, Clone)]
+pub struct GetUserScores {
+    pub(crate) user_id: i64,
+    pub(crate) score_type: UserScoreType,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) include_fails: Option<bool>,
+    pub(crate) limit: Option<i8>,
+    pub(crate) offset: Option<i64>,
+}
+
+impl GetUserScores {
+    pub fn new(user_id: i64, score_type: UserScoreType) -> GetUserScores {
+        GetUserScores { user_id, score_type, mode: None, include_fails: None, limit: None, offset: None }
+    }
+    /// 默认为用户设置的模式
+    pub fn mode(mut self, mode: Mode) -> GetUserScores {
+        self.mode = Some(mode);
+        self
+    }
+    /// 是否包括失败的成绩, 只对 recent 有效
+    pub fn include_fails(mut self, include_fails: bool) -> GetUserScores {
+        self.include_fails = Some(include_fails);
+        self
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetUserScores {
+        self.limit = Some(limit);
+        self
+    }
+    /// 跳过前面的数量, 用来翻页
+    pub fn offset(mut self, offset: i64) -> GetUserScores {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl Request for GetUserScores {
+    type Output = Vec<Score>;
+    const ENDPOINT: &'static str = "users/{user}/scores/{type}";
+
+    fn path(&self) -> String {
+        format!("users/{}/scores/{}", self.user_id, self.score_type.name())
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_page(self.limit, self.offset)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("mode", DataType::Mode(self.mode, true)),
+            ("include_fails", DataType::Int8(self.include_fails.map(i8::from))),
+            ("limit", DataType::Int8(self.limit)),
+            ("offset", DataType::Int64(self.offset)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// 用户谱面列表的类型, most_played 返回的数据不一样, 见 [`GetUserMostPlayed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserBeatmapsetType {
+    /// 收藏的谱面
+    Favourite,
+    Graveyard,
+    /// 参与 (guest diff) 的谱面
+    Guest,
+    Loved,
+    /// 提名过的谱面
+    Nominated,
+    /// wip / pending
+    Pending,
+    /// ranked / approved / qualified
+    Ranked,
+}
+
+impl UserBeatmapsetType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserBeatmapsetType::Favourite => "favourite",
+            UserBeatmapsetType::Graveyard => "graveyard",
+            UserBeatmapsetType::Guest => "guest",
+            UserBeatmapsetType::Loved => "loved",
+            UserBeatmapsetType::Nominated => "nominated",
+            UserBeatmapsetType::Pending => "pending",
+            UserBeatmapsetType::Ranked => "ranked",
+        }
+    }
+}
+
+/// 检查翻页参数
+fn check_page(limit: Option<i8>, offset: Option<i64>) -> Result<()> {
+    check_limit("limit", limit.map(i64::from), 100)?;
+    match offset {
+        Some(offset) if offset < 0 => Err(Error::InvalidInput(format!("offset 不能小于 0, 实际为 {}", offset))),
+        _ => Ok(()),
+    }
+}
+
+/// # GET /users/{user}/beatmapsets/{type}
+/// 见 [`ApiV2::get_user_beatmapsets`](crate::ApiV2::get_user_beatmapsets)
+#[derive(Debug, Clone)]
+pub struct GetUserBeatmapsets {
+    pub(crate) user_id: i64,
+    pub(crate) set_type: UserBeatmapsetType,
+    pub(crate) limit: Option<i8>,
+    pub(crate) offset: Option<i64>,
+}
+
+impl GetUserBeatmapsets {
+    pub fn new(user_id: i64, set_type: UserBeatmapsetType) -> GetUserBeatmapsets {
+        GetUserBeatmapsets { user_id, set_type, limit: None, offset: None }
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetUserBeatmapsets {
+        self.limit = Some(limit);
+        self
+    }
+    /// 跳过前面的数量, 用来翻页
+    pub fn offset(mut self, offset: i64) -> GetUserBeatmapsets {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl Request for GetUserBeatmapsets {
+    type Output = Vec<Beatmapset>;
+    const ENDPOINT: &'static str = "users/{user}/beatmapsets/{type}";
+
+    fn path(&self) -> String {
+        format!("users/{}/beatmapsets/{}", self.user_id, self.set_type.name())
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_page(self.limit, self.offset)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("limit", DataType::Int8(self.limit)),
+            ("offset", DataType::Int64(self.offset)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// # GET /users/{user}/beatmapsets/most_played
+/// 见 [`ApiV2::get_user_most_played`](crate::ApiV2::get_user_most_played)
+#[derive(Debug, Clone)]
+pub struct GetUserMostPlayed {
+    pub(crate) user_id: i64,
+    pub(crate) limit: Option<i8>,
+    pub(crate) offset: Option<i64>,
+}
+
+impl GetUserMostPlayed {
+    pub fn new(user_id: i64) -> GetUserMostPlayed {
+        GetUserMostPlayed { user_id, limit: None, offset: None }
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetUserMostPlayed {
+        self.limit = Some(limit);
+        self
+    }
+    /// 跳过前面的数量, 用来翻页
+    pub fn offset(mut self, offset: i64) -> GetUserMostPlayed {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl Request for GetUserMostPlayed {
+    type Output = Vec<BeatmapPlaycount>;
+    const ENDPOINT: &'static str = "users/{user}/beatmapsets/most_played";
+
+    fn path(&self) -> String {
+        format!("users/{}/beatmapsets/most_played", self.user_id)
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_page(self.limit, self.offset)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("limit", DataType::Int8(self.limit)),
+            ("offset", DataType::Int64(self.offset)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
+
+/// # GET /users/{user}/recent_activity
+/// 见 [`ApiV2::get_user_recent_activity`](crate::ApiV2::get_user_recent_activity)
+#[derive(Debug, Clone)]
+pub struct GetUserRecentActivity {
+    pub(crate) user_id: i64,
+    pub(crate) limit: Option<i8>,
+    pub(crate) offset: Option<i64>,
+}
+
+impl GetUserRecentActivity {
+    pub fn new(user_id: i64) -> GetUserRecentActivity {
+        GetUserRecentActivity { user_id, limit: None, offset: None }
+    }
+    /// 返回的数量, 1-100
+    pub fn limit(mut self, limit: i8) -> GetUserRecentActivity {
+        self.limit = Some(limit);
+        self
+    }
+    /// 跳过前面的数量, 用来翻页
+    pub fn offset(mut self, offset: i64) -> GetUserRecentActivity {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl Request for GetUserRecentActivity {
+    type Output = Vec<RecentActivity>;
+    const ENDPOINT: &'static str = "users/{user}/recent_activity";
+
+    fn path(&self) -> String {
+        format!("users/{}/recent_activity", self.user_id)
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        check_page(self.limit, self.offset)?;
+        let mut vec = vec![];
+        assembly_data(&[
+            ("limit", DataType::Int8(self.limit)),
+            ("offset", DataType::Int64(self.offset)),
+        ], &mut vec);
+        Ok(vec)
+    }
+
+    fn parse(&self, data: String) -> Result<Self::Output> {
+        data_serialize_vec(data)
+    }
+}
@@ -0,0 +1,276 @@
+//! 使用 [`FakeTransport`] 测试请求层的行为 (重试, 缓存, token 刷新, 爬取)
+
+use std::collections::HashSet;
+use std::time::Duration;
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use osu_api_rs::{ApiV1, ApiV2};
+use osu_api_rs::error::Error;
+use osu_api_rs::http::{Cache, FakeTransport, HttpRequest, HttpResponse, RetryPolicy};
+use osu_api_rs::oauth_v2::Token;
+use reqwest::{Method, StatusCode};
+
+const BEATMAPS: &str = "/api/get_beatmaps";
+const TOKEN: &str = "/oauth/token";
+const LOOKUP: &str = "/api/v2/beatmaps/lookup";
+
+/// 重试时几乎不等待
+fn fast_retry() -> RetryPolicy {
+    RetryPolicy::new().base_delay(Duration::from_millis(1))
+}
+
+fn api_v1(transport: &FakeTransport) -> ApiV1 {
+    ApiV1::with_transport("key", transport.clone()).with_retry(fast_retry())
+}
+
+#[tokio::test]
+async fn retry_5xx_with_api_error_body() {
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::new(StatusCode::INTERNAL_SERVER_ERROR, r#"{"error":"boom"}"#));
+    let err = api_v1(&transport).get_beatmap(Some(1)).await.unwrap_err();
+    assert!(matches!(err, Error::Status { status: 500, ref body } if body == "boom"), "{:?}", err);
+    assert_eq!(err.status(), Some(500));
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn retry_5xx_then_success() {
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::new(StatusCode::BAD_GATEWAY, "<html>bad gateway</html>"))
+        .on_get(BEATMAPS, HttpResponse::ok("[]"));
+    let err = api_v1(&transport).get_beatmap(Some(1)).await.unwrap_err();
+    // 重试后拿到了空列表
+    assert!(matches!(err, Error::Null), "{:?}", err);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn retry_429() {
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::new(StatusCode::TOO_MANY_REQUESTS, ""))
+        .on_get(BEATMAPS, HttpResponse::ok("[]"));
+    let _ = api_v1(&transport).get_beatmap(Some(1)).await;
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn no_retry_4xx() {
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::new(StatusCode::NOT_FOUND, r#"{"error":"not found"}"#));
+    let err = api_v1(&transport).get_beatmap(Some(1)).await.unwrap_err();
+    assert_eq!(err.status(), Some(404));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn no_retry_when_disabled() {
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, ""));
+    let api = api_v1(&transport).with_retry(RetryPolicy::none());
+    assert_eq!(api.get_beatmap(Some(1)).await.unwrap_err().status(), Some(503));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn cache_hit() {
+    let transport = FakeTransport::new().on_get(BEATMAPS, v1_page(1..2));
+    let api = api_v1(&transport).with_cache(Cache::memory(16).ttl("get_beatmaps", Duration::from_secs(60)));
+    let _ = api.get_beatmap(Some(1)).await;
+    let _ = api.get_beatmap(Some(1)).await;
+    assert_eq!(transport.requests().len(), 1);
+    // 参数不同, 不命中
+    let _ = api.get_beatmap(Some(2)).await;
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn cache_skips_errors_and_disabled_endpoints() {
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::new(StatusCode::NOT_FOUND, ""))
+        .on_get(BEATMAPS, v1_page(1..2));
+    let api = api_v1(&transport).with_cache(Cache::memory(16).ttl("get_beatmaps", Duration::from_secs(60)));
+    let _ = api.get_beatmap(Some(1)).await;
+    let _ = api.get_beatmap(Some(1)).await;
+    assert_eq!(transport.requests().len(), 2);
+
+    let transport = FakeTransport::new().on_get(BEATMAPS, v1_page(1..2));
+    let api = api_v1(&transport).with_cache(Cache::recommended(16).no_cache("get_beatmaps"));
+    let _ = api.get_beatmap(Some(1)).await;
+    let _ = api.get_beatmap(Some(1)).await;
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn cache_skips_unparsable_bodies() {
+    // v1 出错时状态码也是 200
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, HttpResponse::ok(r#"{"error":"Please provide a valid API key."}"#))
+        .on_get(BEATMAPS, v1_page(1..2));
+    let api = api_v1(&transport).with_cache(Cache::memory(16).ttl("get_beatmaps", Duration::from_secs(60)));
+    assert!(matches!(api.get_beatmap(Some(1)).await, Err(Error::Api(_))));
+    assert_eq!(api.get_beatmap(Some(1)).await.unwrap().beatmap_id, 1);
+    assert_eq!(api.get_beatmap(Some(1)).await.unwrap().beatmap_id, 1);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn cache_key_includes_scheme_and_port() {
+    let transport = FakeTransport::new().on_get(BEATMAPS, v1_page(1..2));
+    let cache = Cache::memory(16).ttl("get_beatmaps", Duration::from_secs(60));
+    for base_url in ["http://127.0.0.1:8080/api", "http://127.0.0.1:8081/api", "https://127.0.0.1:8080/api", "http://127.0.0.1:8080/api"] {
+        let api = api_v1(&transport).with_base_url(base_url).with_cache(cache.clone());
+        api.get_beatmap(Some(1)).await.unwrap();
+    }
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn cache_separates_tokens() {
+    let transport = FakeTransport::new().on_get("/api/v2/beatmaps/1/scores", HttpResponse::ok(r#"{"scores":[]}"#));
+    let cache = Cache::memory(16).ttl("beatmaps/{beatmap}/scores", Duration::from_secs(60));
+    let alice = api_v2(&transport, token("alice", 86400, None)).with_cache(cache.clone());
+    let bob = api_v2(&transport, token("bob", 86400, None)).with_cache(cache.clone());
+    alice.get_beatmap_score(1, None, None, Some("friend")).await.unwrap();
+    bob.get_beatmap_score(1, None, None, Some("friend")).await.unwrap();
+    alice.get_beatmap_score(1, None, None, Some("friend")).await.unwrap();
+    let tokens: Vec<_> = transport.requests().iter().map(|req| bearer(req).to_string()).collect();
+    assert_eq!(tokens, ["Bearer alice", "Bearer bob"]);
+}
+
+fn token(access_token: &str, expires_in: i64, refresh_token: Option<&str>) -> Token {
+    serde_json::from_value(serde_json::json!({
+        "token_type": "Bearer",
+        "access_token": access_token,
+        "expires_in": expires_in,
+        "refresh_token": refresh_token,
+    })).unwrap()
+}
+
+fn token_response(access_token: &str, refresh_token: &str) -> HttpResponse {
+    HttpResponse::ok(serde_json::to_string(&token(access_token, 86400, Some(refresh_token))).unwrap())
+}
+
+fn api_v2(transport: &FakeTransport, token: Token) -> ApiV2 {
+    ApiV2::builder("1", "secret").transport(transport.clone()).build_with_token(token)
+}
+
+fn bearer(request: &HttpRequest) -> &str {
+    request.headers["authorization"].to_str().unwrap()
+}
+
+fn count(transport: &FakeTransport, method: Method, path: &str) -> usize {
+    transport.requests().iter().filter(|req| req.method == method && req.url.path() == path).count()
+}
+
+#[tokio::test]
+async fn refresh_expired_token_before_request() {
+    let transport = FakeTransport::new()
+        .on_post(TOKEN, token_response("new", "r2"))
+        .on_get(LOOKUP, HttpResponse::ok("{}"));
+    let api = api_v2(&transport, token("old", 0, Some("r1")));
+    let _ = api.lookup_beatmap(1).await;
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    let body: serde_json::Value = serde_json::from_slice(requests[0].body.as_deref().unwrap()).unwrap();
+    assert_eq!(body["grant_type"], "refresh_token");
+    assert_eq!(body["refresh_token"], "r1");
+    assert_eq!(bearer(&requests[1]), "Bearer new");
+    assert_eq!(api.token().refresh_token.as_deref(), Some("r2"));
+}
+
+#[tokio::test]
+async fn refresh_and_retry_once_on_401() {
+    let transport = FakeTransport::new()
+        .on_post(TOKEN, token_response("new", "r2"))
+        .on_get(LOOKUP, HttpResponse::new(StatusCode::UNAUTHORIZED, ""))
+        .on_get(LOOKUP, HttpResponse::ok("{}"));
+    let api = api_v2(&transport, token("old", 86400, Some("r1")));
+    let _ = api.lookup_beatmap(1).await;
+    let requests = transport.requests();
+    assert_eq!(count(&transport, Method::POST, TOKEN), 1);
+    assert_eq!(count(&transport, Method::GET, LOOKUP), 2);
+    assert_eq!(bearer(&requests[0]), "Bearer old");
+    assert_eq!(bearer(&requests[2]), "Bearer new");
+}
+
+#[tokio::test]
+async fn give_up_after_second_401() {
+    let transport = FakeTransport::new()
+        .on_post(TOKEN, token_response("new", "r2"))
+        .on_get(LOOKUP, HttpResponse::new(StatusCode::UNAUTHORIZED, r#"{"error":"revoked"}"#));
+    let api = api_v2(&transport, token("old", 86400, Some("r1")));
+    let err = api.lookup_beatmap(1).await.unwrap_err();
+    assert!(matches!(err, Error::Unauthorized(_)), "{:?}", err);
+    assert_eq!(count(&transport, Method::GET, LOOKUP), 2);
+}
+
+#[tokio::test]
+async fn concurrent_requests_refresh_once() {
+    let transport = FakeTransport::new()
+        .on_post(TOKEN, token_response("new", "r2"))
+        .on_get(LOOKUP, HttpResponse::ok("{}"));
+    let api = api_v2(&transport, token("old", 0, Some("r1")));
+    let requests = (0..10).map(|_| {
+        let api = api.clone();
+        tokio::spawn(async move { api.lookup_beatmap(1).await })
+    });
+    for request in futures::future::join_all(requests).await {
+        let _ = request.unwrap();
+    }
+    assert_eq!(count(&transport, Method::POST, TOKEN), 1);
+    assert_eq!(count(&transport, Method::GET, LOOKUP), 10);
+    assert!(transport.requests().iter().filter(|req| req.method == Method::GET).all(|req| bearer(req) == "Bearer new"));
+}
+
+/// v1 的谱面, approved_date 为 2020-01-01 00:00:00 之后的第 `id` 秒
+fn v1_beatmap(id: i64) -> serde_json::Value {
+    let date = (Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap() + chrono::Duration::seconds(id))
+        .format("%Y-%m-%d %H:%M:%S").to_string();
+    serde_json::json!({
+        "beatmapset_id": "1", "beatmap_id": id.to_string(), "approved": "1",
+        "total_length": "100", "hit_length": "90", "version": "Hard", "file_md5": "md5",
+        "diff_size": "4", "diff_overall": "8", "diff_approach": "9", "diff_drain": "6", "mode": "0",
+        "count_normal": "1", "count_slider": "1", "count_spinner": "0",
+        "submit_date": date, "approved_date": date, "last_update": date,
+        "artist": "a", "title": "t", "creator": "c", "creator_id": "2", "bpm": "180",
+        "source": "", "tags": "", "genre_id": "2", "language_id": "3", "favourite_count": "0",
+        "rating": "9", "storyboard": "0", "video": "0", "download_unavailable": "0", "audio_unavailable": "0",
+        "playcount": "0", "passcount": "0", "packs": null, "max_combo": "100",
+        "diff_aim": null, "diff_speed": null, "difficultyrating": "5",
+    })
+}
+
+fn v1_page(ids: std::ops::Range<i64>) -> HttpResponse {
+    HttpResponse::ok(serde_json::Value::Array(ids.map(v1_beatmap).collect()).to_string())
+}
+
+#[tokio::test]
+async fn crawl_beatmaps_dedups_overlapping_pages() {
+    // 每页 500 个, 下一页从上一页最后一个谱面的前一秒开始, 和上一页重叠
+    let transport = FakeTransport::new()
+        .on_get(BEATMAPS, v1_page(0..500))
+        .on_get(BEATMAPS, v1_page(498..998))
+        .on_get(BEATMAPS, v1_page(996..1100));
+    let api = api_v1(&transport);
+    let ids: Vec<i64> = api.crawl_beatmaps(None, None)
+        .map(|beatmap| beatmap.unwrap().beatmap_id)
+        .collect().await;
+    assert_eq!(ids, (0..1100).collect::<Vec<_>>());
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 3);
+    let since = |req: &HttpRequest| req.url.query_pairs().find(|(k, _)| k == "since").map(|(_, v)| v.into_owned());
+    assert_eq!(since(&requests[0]), None);
+    assert_eq!(since(&requests[1]).as_deref(), Some("2020-01-01 00:08:18"));
+}
+
+#[tokio::test]
+async fn debug_does_not_leak_secrets() {
+    let api = ApiV2::builder("1", "secret-789").transport(FakeTransport::new())
+        .build_with_token(token("access-123", 86400, Some("refresh-456")));
+    let debug = format!("{:?}", api);
+    for secret in ["secret-789", "access-123", "refresh-456"] {
+        assert!(!debug.contains(secret), "{}", debug);
+    }
+}
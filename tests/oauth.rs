@@ -0,0 +1,168 @@
+//! 授权码模式: 授权链接, 回调接收, authorization_code / refresh_token 换取 token
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use osu_api_rs::ApiV2;
+use osu_api_rs::error::Error;
+use osu_api_rs::http::{FakeTransport, HttpResponse};
+use osu_api_rs::oauth_v2::{self, Scope, Token};
+use reqwest::StatusCode;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+
+const TOKEN: &str = "/oauth/token";
+const REDIRECT_URI: &str = "http://127.0.0.1:7270";
+
+fn token_body(access_token: &str, refresh_token: Option<&str>) -> HttpResponse {
+    HttpResponse::ok(serde_json::json!({
+        "token_type": "Bearer",
+        "access_token": access_token,
+        "expires_in": 86400,
+        "refresh_token": refresh_token,
+    }).to_string())
+}
+
+fn request_body(transport: &FakeTransport, index: usize) -> serde_json::Value {
+    serde_json::from_slice(transport.requests()[index].body.as_deref().unwrap()).unwrap()
+}
+
+#[test]
+fn authorization_url() {
+    let builder = ApiV2::builder("1", "secret")
+        .redirect_uri(REDIRECT_URI)
+        .scopes([Scope::Identify, Scope::Public, Scope::FriendsRead]);
+    let url = builder.authorization_url("abc").unwrap();
+    assert_eq!(url.as_str().split('?').next(), Some(oauth_v2::OSU_API_2_AUTHORIZE));
+    let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let expected = [
+        ("client_id", "1"),
+        ("redirect_uri", REDIRECT_URI),
+        ("response_type", "code"),
+        ("scope", "identify public friends.read"),
+        ("state", "abc"),
+    ];
+    assert_eq!(query, expected.map(|(k, v)| (k.to_string(), v.to_string())));
+}
+
+#[test]
+fn authorization_url_needs_redirect_uri() {
+    let err = ApiV2::builder("1", "secret").authorization_url("abc").unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)), "{:?}", err);
+}
+
+#[test]
+fn new_state() {
+    let state = oauth_v2::new_state();
+    assert_eq!(state.len(), 32);
+    assert!(state.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(state, oauth_v2::new_state());
+}
+
+#[tokio::test]
+async fn authorization_code_grant() {
+    let transport = FakeTransport::new().on_post(TOKEN, token_body("access", Some("refresh")));
+    let api = ApiV2::builder("1", "secret")
+        .transport(transport.clone())
+        .redirect_uri(REDIRECT_URI)
+        .scopes([Scope::Identify, Scope::Public])
+        .build_with_code("the-code").await.unwrap();
+    let body = request_body(&transport, 0);
+    assert_eq!(body["grant_type"], "authorization_code");
+    assert_eq!(body["code"], "the-code");
+    assert_eq!(body["redirect_uri"], REDIRECT_URI);
+    assert_eq!(body["client_id"], "1");
+    assert_eq!(body["client_secret"], "secret");
+    let token = api.token();
+    assert_eq!(token.access_token, "access");
+    assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+    assert_eq!(token.scopes, [Scope::Identify, Scope::Public]);
+}
+
+#[tokio::test]
+async fn authorization_code_rejected() {
+    let transport = FakeTransport::new()
+        .on_post(TOKEN, HttpResponse::new(StatusCode::BAD_REQUEST, r#"{"error":"invalid_grant"}"#));
+    let err = ApiV2::builder("1", "secret")
+        .transport(transport)
+        .redirect_uri(REDIRECT_URI)
+        .build_with_code("used-code").await.unwrap_err();
+    assert!(matches!(err, Error::Auth(ref msg) if msg.contains("invalid_grant")), "{:?}", err);
+}
+
+#[tokio::test]
+async fn refresh_token_grant() {
+    // 新的 token 中没有 refresh token 时沿用旧的
+    let transport = FakeTransport::new().on_post(TOKEN, token_body("new", None));
+    let token: Token = serde_json::from_value(serde_json::json!({
+        "token_type": "Bearer", "access_token": "old", "expires_in": 86400, "refresh_token": "r1",
+    })).unwrap();
+    let api = ApiV2::builder("1", "secret")
+        .transport(transport.clone())
+        .scopes([Scope::Identify, Scope::Public])
+        .build_with_token(token);
+    api.refresh().await.unwrap();
+    let body = request_body(&transport, 0);
+    assert_eq!(body["grant_type"], "refresh_token");
+    assert_eq!(body["refresh_token"], "r1");
+    assert_eq!(body["scope"], "identify public");
+    assert_eq!(api.token().access_token, "new");
+    assert_eq!(api.token().refresh_token.as_deref(), Some("r1"));
+}
+
+/// 在空闲端口上启动 `wait_for_code`
+async fn listen(state: &str) -> (SocketAddr, JoinHandle<Result<String, Error>>) {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let state = state.to_string();
+    let handle = tokio::spawn(async move { oauth_v2::wait_for_code(addr, &state).await });
+    for _ in 0..100 {
+        if TcpStream::connect(addr).await.is_ok() {
+            return (addr, handle);
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("wait_for_code 没有开始监听");
+}
+
+/// 发送一个 GET 请求, 返回响应的状态行
+async fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(format!("GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, addr).as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+async fn result(handle: JoinHandle<Result<String, Error>>) -> Result<String, Error> {
+    tokio::time::timeout(Duration::from_secs(5), handle).await.expect("wait_for_code 没有返回").unwrap()
+}
+
+#[tokio::test]
+async fn wait_for_code_returns_code() {
+    let (addr, handle) = listen("s1").await;
+    assert_eq!(get(addr, "/favicon.ico").await, "HTTP/1.1 404 Not Found");
+    assert_eq!(get(addr, "/?code=abc&state=s1").await, "HTTP/1.1 200 OK");
+    assert_eq!(result(handle).await.unwrap(), "abc");
+}
+
+#[tokio::test]
+async fn wait_for_code_not_blocked_by_idle_connection() {
+    let (addr, handle) = listen("s1").await;
+    // 浏览器预连接: 打开连接但不发送请求
+    let _idle = TcpStream::connect(addr).await.unwrap();
+    let mut garbage = TcpStream::connect(addr).await.unwrap();
+    garbage.write_all(b"\r\n\r\n").await.unwrap();
+    assert_eq!(get(addr, "/?code=abc&state=s1").await, "HTTP/1.1 200 OK");
+    assert_eq!(result(handle).await.unwrap(), "abc");
+}
+
+#[tokio::test]
+async fn wait_for_code_ignores_wrong_state() {
+    let (addr, handle) = listen("s1").await;
+    assert_eq!(get(addr, "/?error=x").await, "HTTP/1.1 400 Bad Request");
+    assert_eq!(get(addr, "/?code=evil&state=s2").await, "HTTP/1.1 400 Bad Request");
+    assert!(!handle.is_finished());
+    assert_eq!(get(addr, "/?error=access_denied&state=s1").await, "HTTP/1.1 200 OK");
+    let err = result(handle).await.unwrap_err();
+    assert!(matches!(err, Error::Auth(ref msg) if msg.contains("access_denied")), "{:?}", err);
+}
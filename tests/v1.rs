@@ -0,0 +1,111 @@
+//! 用保存下来的返回数据测试 v1 实体的反序列化
+
+use chrono::{TimeZone, Utc};
+use osu_api_rs::{ApiV1, Mode, Mods, UserType};
+use osu_api_rs::http::{FakeTransport, HttpResponse};
+
+fn api_v1(transport: &FakeTransport) -> ApiV1 {
+    ApiV1::with_transport("key", transport.clone())
+}
+
+#[tokio::test]
+async fn get_user() {
+    let transport = FakeTransport::new()
+        .on_get("/api/get_user", HttpResponse::ok(include_str!("fixtures/v1_get_user.json")));
+    let user = api_v1(&transport).get_user(UserType::USERID(18267600), None, None).await.unwrap();
+    assert_eq!(user.user_id, 18267600);
+    assert_eq!(user.join_date, Utc.with_ymd_and_hms(2020, 8, 29, 12, 31, 9).unwrap());
+    assert_eq!(user.pp_raw, Some(5123.45));
+    assert_eq!(user.count_rank_ssh, Some(3));
+
+    let events = &user.events;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].beatmap_id, Some(252002));
+    assert_eq!(events[0].beatmapset_id, Some(93398));
+    assert_eq!(events[0].date, Utc.with_ymd_and_hms(2022, 8, 12, 13, 27, 45).unwrap());
+    assert_eq!(events[0].epicfactor, Some(1));
+    assert!(events[1].display_html.contains("Impeccable"));
+    assert_eq!(events[1].beatmap_id, None);
+    assert_eq!(events[1].epicfactor, Some(4));
+}
+
+#[tokio::test]
+async fn get_scores() {
+    let transport = FakeTransport::new()
+        .on_get("/api/get_scores", HttpResponse::ok(include_str!("fixtures/v1_get_scores.json")));
+    let scores = api_v1(&transport)
+        .get_scores(Some(252002), UserType::USERID(18267600), Some(Mode::Osu), None, Some(2)).await.unwrap();
+    assert_eq!(scores.len(), 2);
+    let score = &scores[0];
+    assert_eq!(score.score_id, 4219586531);
+    assert_eq!(score.score, 48766652);
+    assert_eq!(score.maxcombo, 1238);
+    assert_eq!((score.count300, score.count100, score.count50, score.countmiss), (971, 14, 0, 1));
+    assert!(!score.perfect);
+    assert_eq!(score.enabled_mods, Mods::HD | Mods::DT);
+    assert_eq!(score.date, Utc.with_ymd_and_hms(2022, 8, 12, 13, 27, 45).unwrap());
+    assert_eq!(score.pp, Some(412.873));
+    assert!(score.replay_available);
+    // 未计算 pp 的成绩
+    assert_eq!(scores[1].pp, None);
+    assert!(scores[1].enabled_mods.is_empty());
+    assert!(!scores[1].replay_available);
+}
+
+#[tokio::test]
+async fn get_user_bp_list() {
+    let transport = FakeTransport::new()
+        .on_get("/api/get_user_best", HttpResponse::ok(include_str!("fixtures/v1_get_user_best.json")));
+    let list = api_v1(&transport).get_user_bp_list(UserType::USERID(18267600), None, Some(1)).await.unwrap();
+    assert_eq!(list.len(), 1);
+    let record = &list[0];
+    assert_eq!(record.beatmap_id, 252002);
+    assert_eq!(record.score_id, Some(4219586531));
+    assert_eq!(record.enabled_mods, Mods::HD | Mods::DT | Mods::NC);
+    assert_eq!(record.pp, Some(412.873));
+    assert_eq!(record.date, Utc.with_ymd_and_hms(2022, 8, 12, 13, 27, 45).unwrap());
+}
+
+#[tokio::test]
+async fn get_user_recent_list() {
+    // 最近游玩 (包括失败) 的记录没有 score_id, pp 和 replay_available
+    let transport = FakeTransport::new()
+        .on_get("/api/get_user_recent", HttpResponse::ok(include_str!("fixtures/v1_get_user_recent.json")));
+    let list = api_v1(&transport).get_user_recent_list(UserType::USERID(18267600), None, Some(1)).await.unwrap();
+    assert_eq!(list.len(), 1);
+    let record = &list[0];
+    assert_eq!(record.beatmap_id, 658127);
+    assert_eq!(record.score_id, None);
+    assert_eq!(record.pp, None);
+    assert!(!record.replay_available);
+    assert_eq!(record.rank, "F");
+}
+
+#[tokio::test]
+async fn get_match() {
+    let transport = FakeTransport::new()
+        .on_get("/api/get_match", HttpResponse::ok(include_str!("fixtures/v1_get_match.json")));
+    let room = api_v1(&transport).get_match(Some(105537044)).await.unwrap();
+    assert_eq!(room.room_info.match_id, 105537044);
+    assert_eq!(room.room_info.start_time, Utc.with_ymd_and_hms(2022, 11, 26, 8, 0, 12).unwrap());
+    // 进行中的房间
+    assert_eq!(room.room_info.end_time, None);
+
+    assert_eq!(room.games.len(), 1);
+    let game = &room.games[0];
+    assert_eq!(game.game_id, 560180453);
+    assert_eq!(game.end_time, Some(Utc.with_ymd_and_hms(2022, 11, 26, 8, 9, 10).unwrap()));
+    assert_eq!(game.play_mode, Mode::Osu);
+    assert_eq!(game.match_type, Some(0));
+    assert_eq!((game.scoring_type, game.team_type), (3, 2));
+    assert_eq!(game.mods, Mods::NF);
+
+    assert_eq!(game.scores.len(), 2);
+    assert_eq!(game.scores[0].user_id, 18267600);
+    assert_eq!(game.scores[0].enabled_mods, Some(Mods::HD));
+    assert!(game.scores[0].pass);
+    // 非 free mod 房间中 enabled_mods 为 null
+    assert_eq!(game.scores[1].slot, 4);
+    assert_eq!(game.scores[1].enabled_mods, None);
+    assert!(!game.scores[1].pass);
+}
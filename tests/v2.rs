@@ -0,0 +1,302 @@
+//! 用保存下来的返回数据测试 v2 实体的反序列化
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use osu_api_rs::{ApiV2, GameMod, Mode, Mods, RankStatus, UserType};
+use osu_api_rs::entity_v2::{Event, RecentActivity};
+use osu_api_rs::error::Result;
+use osu_api_rs::http::{BoxFuture, FakeTransport, HttpRequest, HttpResponse, HttpTransport};
+use osu_api_rs::oauth_v2::Token;
+use osu_api_rs::request_v2::{UserBeatmapsetType, UserScoreType};
+
+fn api_v2<T: HttpTransport + Clone + 'static>(transport: &T) -> ApiV2 {
+    let token: Token = serde_json::from_str(r#"{"token_type":"Bearer","access_token":"token","expires_in":86400}"#).unwrap();
+    ApiV2::builder("1", "secret").transport(transport.clone()).build_with_token(token)
+}
+
+#[tokio::test]
+async fn user_scores_best() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/18267600/scores/best", HttpResponse::ok(include_str!("fixtures/v2_user_scores_best.json")));
+    let scores = api_v2(&transport)
+        .get_user_scores(18267600, UserScoreType::Best, Some(Mode::Osu), None, Some(1), None).await.unwrap();
+    let score = &scores[0];
+    assert_eq!(score.best_id, Some(4219586531));
+    assert_eq!(score.pp, Some(412.873));
+    assert_eq!(GameMod::to_legacy(&score.mods), Some(Mods::HD | Mods::DT));
+    assert_eq!(score.user.username, "example");
+    assert!(score.user.country.is_none() && score.user.cover.is_none());
+    assert_eq!(score.beatmapset.as_ref().unwrap().title, "High-Priestess");
+    assert_eq!(score.weight.as_ref().unwrap().percentage, 100.0);
+}
+
+#[tokio::test]
+async fn user_scores_recent_failed_on_loved() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/18267600/scores/recent", HttpResponse::ok(include_str!("fixtures/v2_user_scores_recent.json")));
+    let scores = api_v2(&transport)
+        .get_user_scores(18267600, UserScoreType::Recent, None, Some(true), None, None).await.unwrap();
+    let score = &scores[0];
+    assert!(!score.passed);
+    assert_eq!(score.best_id, None);
+    assert_eq!(score.pp, None);
+    assert!(score.weight.is_none());
+}
+
+#[tokio::test]
+async fn crawl_user_firsts_with_compact_users() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/18267600/scores/firsts", HttpResponse::ok(include_str!("fixtures/v2_user_scores_best.json")));
+    let api = api_v2(&transport);
+    let scores: Vec<_> = futures::StreamExt::collect(api.crawl_user_firsts(18267600, None)).await;
+    assert_eq!(scores.len(), 1);
+    assert!(scores[0].is_ok());
+}
+
+#[tokio::test]
+async fn user_beatmapsets() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/2/beatmapsets/favourite", HttpResponse::ok(include_str!("fixtures/v2_user_beatmapsets_favourite.json")));
+    let sets = api_v2(&transport).get_user_beatmapsets(2, UserBeatmapsetType::Favourite, None, None).await.unwrap();
+    assert_eq!(sets.len(), 2);
+    assert_eq!(sets[0].id, 93398);
+    assert_eq!(sets[0].beatmaps[0].checksum, "c8f08438204abfcdd1a748ebfae67421");
+    assert_eq!(sets[0].ratings.len(), 11);
+    assert_eq!(sets[1].status, RankStatus::Loved);
+    assert_eq!(sets[1].artist_unicode, "かめりあ");
+    assert_eq!(sets[1].submitted_date, None);
+    assert_eq!(sets[1].legacy_thread_url, None);
+}
+
+#[tokio::test]
+async fn user_most_played() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/2/beatmapsets/most_played", HttpResponse::ok(include_str!("fixtures/v2_user_most_played.json")));
+    let played = api_v2(&transport).get_user_most_played(2, Some(2), None).await.unwrap();
+    assert_eq!(played.len(), 2);
+    assert_eq!((played[0].beatmap_id, played[0].count), (252002, 523));
+    assert_eq!(played[0].beatmap.version, "Overkill");
+    assert_eq!(played[0].beatmapset.title, "High-Priestess");
+    assert_eq!(played[1].beatmap.status, RankStatus::Loved);
+    assert_eq!(transport.requests()[0].url.query(), Some("limit=2"));
+}
+
+/// 用 fixture 中的第一项拼出一页, 每一项的 `id` 字段依次为 ids 中的值
+fn page(fixture: &str, id: &str, ids: std::ops::Range<i64>) -> HttpResponse {
+    let items: Vec<serde_json::Value> = serde_json::from_str(fixture).unwrap();
+    let page: Vec<_> = ids.map(|i| {
+        let mut item = items[0].clone();
+        item[id] = i.into();
+        item
+    }).collect();
+    HttpResponse::ok(serde_json::to_string(&page).unwrap())
+}
+
+fn offsets(transport: &FakeTransport) -> Vec<String> {
+    transport.requests().iter()
+        .map(|req| req.url.query_pairs().find(|(k, _)| k == "offset").map(|(_, v)| v.into_owned()).unwrap_or_default())
+        .collect()
+}
+
+#[tokio::test]
+async fn crawl_user_beatmapsets_stops_on_short_page() {
+    let fixture = include_str!("fixtures/v2_user_beatmapsets_favourite.json");
+    let path = "/api/v2/users/2/beatmapsets/favourite";
+    let transport = FakeTransport::new()
+        .on_get(path, page(fixture, "id", 0..100))
+        .on_get(path, page(fixture, "id", 100..200))
+        .on_get(path, page(fixture, "id", 200..230));
+    let api = api_v2(&transport);
+    let ids: Vec<i64> = futures::StreamExt::collect::<Vec<_>>(api.crawl_user_beatmapsets(2, UserBeatmapsetType::Favourite)).await
+        .into_iter().map(|set| set.unwrap().id).collect();
+    assert_eq!(ids, (0..230).collect::<Vec<_>>());
+    assert_eq!(offsets(&transport), ["0", "100", "200"]);
+    assert!(transport.requests().iter().all(|req| req.url.query_pairs().any(|(k, v)| k == "limit" && v == "100")));
+}
+
+#[tokio::test]
+async fn crawl_user_most_played_stops_on_empty_page() {
+    let fixture = include_str!("fixtures/v2_user_most_played.json");
+    let path = "/api/v2/users/2/beatmapsets/most_played";
+    let transport = FakeTransport::new()
+        .on_get(path, page(fixture, "beatmap_id", 0..100))
+        .on_get(path, HttpResponse::ok("[]"));
+    let api = api_v2(&transport);
+    let played: Vec<_> = futures::StreamExt::collect(api.crawl_user_most_played(2)).await;
+    assert_eq!(played.len(), 100);
+    assert!(played.iter().all(Result::is_ok));
+    assert_eq!(offsets(&transport), ["0", "100"]);
+}
+
+#[tokio::test]
+async fn beatmap_scores_on_loved_map() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/beatmaps/129891/scores", HttpResponse::ok(include_str!("fixtures/v2_beatmap_scores_loved.json")));
+    let scores = api_v2(&transport).get_beatmap_score(129891, Some(Mode::Osu), None, None).await.unwrap().scores;
+    assert_eq!(scores.len(), 2);
+    // loved 谱面没有 pp, 超过 i32 的分数
+    assert!(scores.iter().all(|score| score.pp.is_none()));
+    assert_eq!(scores[0].best_id, None);
+    assert_eq!(scores[0].score, 2_147_484_648);
+    assert_eq!(scores[1].best_id, Some(4100000002));
+    assert_eq!(scores[0].user.country.as_ref().unwrap().code, "AU");
+}
+
+#[tokio::test]
+async fn user_recent_activity() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/18267600/recent_activity", HttpResponse::ok(include_str!("fixtures/v2_user_recent_activity.json")));
+    let activity = api_v2(&transport).get_user_recent_activity(18267600, None, None).await.unwrap();
+    assert_eq!(activity.len(), 8);
+    assert_eq!(activity[0].id, 703018151);
+    match &activity[0].event {
+        Event::Rank { score_rank, rank, mode, beatmap, user } => {
+            assert_eq!((score_rank.as_str(), *rank, *mode), ("SH", 37, Mode::Osu));
+            assert_eq!(beatmap.url, "/b/252002?m=0");
+            assert_eq!(user.username, "example");
+        }
+        other => panic!("{:?}", other),
+    }
+    match &activity[1].event {
+        Event::Achievement { achievement, .. } => {
+            assert_eq!(achievement.name, "Impeccable");
+            assert_eq!(achievement.mode, Some(Mode::Osu));
+            assert_eq!(achievement.instructions, None);
+        }
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(&activity[2].event, Event::RankLost { mode: Mode::Mania, .. }));
+    assert!(matches!(&activity[3].event, Event::BeatmapsetApprove { approval: RankStatus::Loved, .. }));
+    assert!(matches!(&activity[4].event, Event::BeatmapPlaycount { count: 100, .. }));
+    match &activity[5].event {
+        Event::UsernameChange { user } => assert_eq!(user.previous_username.as_deref(), Some("old_example")),
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(&activity[6].event, Event::UserSupportGift { .. }));
+    // 不认识的类型
+    assert!(matches!(&activity[7].event, Event::Unknown));
+    assert_eq!(activity[7].id, 702300006);
+    assert_eq!(activity[7].created_at, "2022-08-05T08:00:00Z".parse::<chrono::DateTime<chrono::Utc>>().unwrap());
+
+    // 序列化后可以再读回来
+    let json = serde_json::to_string(&activity).unwrap();
+    let again: Vec<RecentActivity> = serde_json::from_str(&json).unwrap();
+    assert!(matches!(&again[0].event, Event::Rank { rank: 37, .. }));
+    assert!(matches!(&again[7].event, Event::Unknown));
+}
+
+#[tokio::test]
+async fn get_user() {
+    let transport = FakeTransport::new()
+        .on_get("/api/v2/users/2/osu", HttpResponse::ok(include_str!("fixtures/v2_get_user.json")));
+    let user = api_v2(&transport).get_user(UserType::USERID(2), Some(Mode::Osu)).await.unwrap();
+    assert_eq!(user.username, "peppy");
+    assert_eq!(user.last_visit, None);
+    assert_eq!(user.playmode, Mode::Osu);
+    assert_eq!(user.country.code, "AU");
+    assert_eq!(user.cover.id, None);
+    assert_eq!(user.kudosu.total, 10921);
+    assert_eq!(user.badges[0].image_2x_url.as_deref(), Some("https://assets.ppy.sh/profile-badges/osu-stream@2x.png"));
+    assert_eq!(user.groups[0].playmodes, None);
+    assert_eq!(user.groups[1].playmodes, Some(vec![Mode::Osu, Mode::Taiko]));
+    assert_eq!(user.monthly_playcounts[1].start_date, chrono::NaiveDate::from_ymd_opt(2007, 9, 1).unwrap());
+    assert_eq!(user.previous_usernames, ["PeppyHax"]);
+    assert_eq!(user.rank_highest.as_ref().unwrap().rank, 7133);
+    assert_eq!(user.rank_history.as_ref().unwrap().data.last(), Some(&612003));
+    assert_eq!(user.scores_pinned_count, 1);
+    assert_eq!(user.support_level, 3);
+
+    let statistics = &user.statistics;
+    assert_eq!(statistics.global_rank, Some(612003));
+    assert_eq!(statistics.country_rank, Some(11023));
+    assert_eq!(statistics.level.current, 100);
+    assert_eq!(statistics.grade_counts.ssh, 3);
+    assert_eq!(statistics.play_time, Some(1546813));
+    assert!(statistics.is_ranked);
+}
+
+/// `/users` 中的用户
+fn compact_user(id: i64) -> serde_json::Value {
+    serde_json::json!({
+        "avatar_url": format!("https://a.ppy.sh/{}", id), "country_code": "CN", "default_group": "default",
+        "id": id, "is_active": true, "is_bot": false, "is_deleted": false, "is_online": false,
+        "is_supporter": false, "last_visit": null, "pm_friends_only": false, "profile_colour": null,
+        "username": format!("user{}", id),
+    })
+}
+
+/// 按请求中的 `ids[]` 倒序返回用户 (id 为 0 的不存在), 记录每个请求的 ids 和最多同时进行的请求数
+#[derive(Debug, Clone, Default)]
+struct UsersTransport {
+    state: Arc<UsersState>,
+}
+
+#[derive(Debug, Default)]
+struct UsersState {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    chunks: Mutex<Vec<Vec<i64>>>,
+}
+
+impl HttpTransport for UsersTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let state = &self.state;
+            let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            state.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            let ids: Vec<i64> = request.url.query_pairs()
+                .filter(|(k, _)| k == "ids[]")
+                .map(|(_, v)| v.parse().unwrap())
+                .collect();
+            state.chunks.lock().unwrap().push(ids.clone());
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            state.in_flight.fetch_sub(1, Ordering::SeqCst);
+            let users: Vec<_> = ids.into_iter().rev().filter(|id| *id != 0).map(compact_user).collect();
+            Ok(HttpResponse::ok(serde_json::json!({ "users": users }).to_string()))
+        })
+    }
+}
+
+#[tokio::test]
+async fn get_users_in_order() {
+    let mut ids: Vec<i64> = (1..=120).rev().collect();
+    ids.insert(5, 0);
+    ids.insert(60, 0);
+    let transport = UsersTransport::default();
+    let users = api_v2(&transport).get_users(&ids).await.unwrap();
+    assert_eq!(users.len(), ids.len());
+    for (id, user) in ids.iter().zip(&users) {
+        match user {
+            Some(user) => assert_eq!(user.id, *id),
+            None => assert_eq!(*id, 0),
+        }
+    }
+    // 每 50 个一组
+    let mut chunks = transport.state.chunks.lock().unwrap().clone();
+    chunks.sort_by_key(|chunk| std::cmp::Reverse(chunk.len()));
+    assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), [50, 50, 22]);
+    let mut requested: Vec<i64> = chunks.concat();
+    requested.sort();
+    let mut expected = ids.clone();
+    expected.sort();
+    assert_eq!(requested, expected);
+}
+
+#[tokio::test]
+async fn get_users_limits_concurrency() {
+    let ids: Vec<i64> = (1..=500).collect();
+    let transport = UsersTransport::default();
+    let users = api_v2(&transport).get_users(&ids).await.unwrap();
+    assert!(users.iter().all(Option::is_some));
+    assert_eq!(transport.state.chunks.lock().unwrap().len(), 10);
+    let max_in_flight = transport.state.max_in_flight.load(Ordering::SeqCst);
+    assert!((2..=4).contains(&max_in_flight), "{}", max_in_flight);
+}
+
+#[tokio::test]
+async fn get_users_empty() {
+    let transport = UsersTransport::default();
+    assert!(api_v2(&transport).get_users(&[]).await.unwrap().is_empty());
+    assert!(transport.state.chunks.lock().unwrap().is_empty());
+}